@@ -0,0 +1,156 @@
+use crate::region::Region;
+
+// Output rates in CPU cycles per bit.
+const NTSC_RATES: [u16; 16] = [
+    428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 84, 72, 54,
+];
+const PAL_RATES: [u16; 16] = [
+    398, 354, 316, 298, 276, 236, 210, 198, 176, 148, 132, 118, 98, 78, 66, 50,
+];
+
+/// The delta modulation channel, which plays 1-bit delta-encoded samples
+/// fetched from PRG memory by DMA.
+pub struct Dmc {
+    rates: &'static [u16; 16],
+    irq_enabled: bool,
+    looping: bool,
+    timer_period: u16,
+    timer: u16,
+    output_level: u8,
+
+    sample_addr: u16,
+    sample_length: u16,
+    current_addr: u16,
+    bytes_remaining: u16,
+    sample_buffer: Option<u8>,
+
+    shift_register: u8,
+    bits_remaining: u8,
+    silence: bool,
+
+    pub irq: bool,
+}
+
+impl Dmc {
+    pub fn new(region: Region) -> Self {
+        let rates = match region {
+            Region::NTSC | Region::DENDY => &NTSC_RATES,
+            Region::PAL => &PAL_RATES,
+        };
+        Dmc {
+            rates,
+            irq_enabled: false,
+            looping: false,
+            timer_period: rates[0],
+            timer: 0,
+            output_level: 0,
+            sample_addr: 0xC000,
+            sample_length: 1,
+            current_addr: 0xC000,
+            bytes_remaining: 0,
+            sample_buffer: None,
+            shift_register: 0,
+            bits_remaining: 8,
+            silence: true,
+            irq: false,
+        }
+    }
+
+    /// Writes one of the channel's four registers, `index` 0-3.
+    pub fn write_register(&mut self, index: u16, data: u8) {
+        match index {
+            0 => {
+                self.irq_enabled = data & 0b1000_0000 != 0;
+                if !self.irq_enabled {
+                    self.irq = false;
+                }
+                self.looping = data & 0b0100_0000 != 0;
+                self.timer_period = self.rates[(data & 0b1111) as usize];
+            }
+            1 => self.output_level = data & 0b0111_1111,
+            2 => self.sample_addr = 0xC000 + data as u16 * 64,
+            3 => self.sample_length = data as u16 * 16 + 1,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Driven by bit 4 of $4015: disabling stops the sample, enabling
+    /// restarts it only if it has finished.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.bytes_remaining = 0;
+        } else if self.bytes_remaining == 0 {
+            self.restart();
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.bytes_remaining > 0
+    }
+
+    fn restart(&mut self) {
+        self.current_addr = self.sample_addr;
+        self.bytes_remaining = self.sample_length;
+    }
+
+    /// The address the memory reader needs fetched, once the sample buffer
+    /// has been emptied and bytes of the sample remain.
+    pub fn dma_request(&self) -> Option<u16> {
+        if self.sample_buffer.is_none() && self.bytes_remaining > 0 {
+            Some(self.current_addr)
+        } else {
+            None
+        }
+    }
+
+    /// Completes a DMA fetch requested by `dma_request`.
+    pub fn fill_sample_buffer(&mut self, data: u8) {
+        self.sample_buffer = Some(data);
+        // The address wraps from $FFFF around to $8000.
+        self.current_addr = self.current_addr.checked_add(1).unwrap_or(0x8000);
+        self.bytes_remaining -= 1;
+        if self.bytes_remaining == 0 {
+            if self.looping {
+                self.restart();
+            } else if self.irq_enabled {
+                self.irq = true;
+            }
+        }
+    }
+
+    /// Clocked every CPU cycle.
+    pub fn clock_timer(&mut self) {
+        if self.timer > 0 {
+            self.timer -= 1;
+            return;
+        }
+        self.timer = self.timer_period - 1;
+
+        if !self.silence {
+            if self.shift_register & 1 != 0 {
+                if self.output_level <= 125 {
+                    self.output_level += 2;
+                }
+            } else if self.output_level >= 2 {
+                self.output_level -= 2;
+            }
+        }
+        self.shift_register >>= 1;
+
+        self.bits_remaining -= 1;
+        if self.bits_remaining == 0 {
+            self.bits_remaining = 8;
+            match self.sample_buffer.take() {
+                Some(data) => {
+                    self.silence = false;
+                    self.shift_register = data;
+                }
+                None => self.silence = true,
+            }
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        self.output_level
+    }
+}
@@ -0,0 +1,192 @@
+use crate::region::Region;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameClock {
+    Quarter,
+    /// A half frame clock, which is also a quarter frame clock.
+    Half,
+}
+
+/// Divides the CPU clock into the quarter and half frame clocks that drive
+/// the envelopes, sweeps and length counters, and raises the frame IRQ at
+/// the end of each 4-step sequence.
+pub struct FrameCounter {
+    steps: [u32; 5],
+    cycles: u32,
+    five_step: bool,
+    irq_inhibit: bool,
+    reset_delay: u8,
+    pub irq: bool,
+}
+
+impl FrameCounter {
+    pub fn new(region: Region) -> Self {
+        // CPU cycles after which each step of the sequence fires. The 4-step
+        // sequence ends at the fourth, the 5-step sequence at the fifth.
+        let steps = match region {
+            Region::NTSC | Region::DENDY => [7457, 14913, 22371, 29829, 37281],
+            Region::PAL => [8313, 16627, 24939, 33253, 41565],
+        };
+        FrameCounter {
+            steps,
+            cycles: 0,
+            five_step: false,
+            irq_inhibit: false,
+            reset_delay: 0,
+            irq: false,
+        }
+    }
+
+    /// Handles a $4017 write. The sequence restarts 3 CPU cycles later if
+    /// the write lands on an APU cycle, 4 otherwise.
+    pub fn write(&mut self, data: u8, on_apu_cycle: bool) {
+        self.five_step = data & 0b1000_0000 != 0;
+        self.irq_inhibit = data & 0b0100_0000 != 0;
+        if self.irq_inhibit {
+            self.irq = false;
+        }
+        self.reset_delay = if on_apu_cycle { 3 } else { 4 };
+    }
+
+    /// Advances the sequence by one CPU cycle.
+    pub fn tick(&mut self) -> Option<FrameClock> {
+        if self.reset_delay > 0 {
+            self.reset_delay -= 1;
+            if self.reset_delay == 0 {
+                self.cycles = 0;
+                // Entering 5-step mode clocks everything straight away.
+                return if self.five_step {
+                    Some(FrameClock::Half)
+                } else {
+                    None
+                };
+            }
+        }
+
+        self.cycles += 1;
+        let [first, second, third, fourth, fifth] = self.steps;
+        match self.cycles {
+            c if c == first || c == third => Some(FrameClock::Quarter),
+            c if c == second => Some(FrameClock::Half),
+            c if self.five_step => {
+                if c == fifth {
+                    Some(FrameClock::Half)
+                } else {
+                    if c > fifth {
+                        self.cycles = 0;
+                    }
+                    None
+                }
+            }
+            // The IRQ flag is asserted on three consecutive cycles around
+            // the last step of the 4-step sequence.
+            c if c == fourth - 1 => {
+                self.set_irq();
+                None
+            }
+            c if c == fourth => {
+                self.set_irq();
+                Some(FrameClock::Half)
+            }
+            c if c > fourth => {
+                self.set_irq();
+                self.cycles = 0;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn set_irq(&mut self) {
+        if !self.irq_inhibit {
+            self.irq = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Ticks for `cycles` CPU cycles after a $4017 write, returning each
+    // clock with the number of cycles since the write.
+    fn run(counter: &mut FrameCounter, cycles: u32) -> Vec<(u32, FrameClock)> {
+        (1..=cycles)
+            .filter_map(|cycle| counter.tick().map(|clock| (cycle, clock)))
+            .collect()
+    }
+
+    fn counter_after_write(data: u8) -> FrameCounter {
+        let mut counter = FrameCounter::new(Region::NTSC);
+        counter.write(data, true);
+        counter
+    }
+
+    #[test]
+    fn four_step_sequence() {
+        let mut counter = counter_after_write(0x00);
+        assert_eq!(
+            run(&mut counter, 3 + 29830 + 7457),
+            [
+                (3 + 7457, FrameClock::Quarter),
+                (3 + 14913, FrameClock::Half),
+                (3 + 22371, FrameClock::Quarter),
+                (3 + 29829, FrameClock::Half),
+                (3 + 29830 + 7457, FrameClock::Quarter),
+            ]
+        );
+    }
+
+    #[test]
+    fn five_step_sequence() {
+        let mut counter = counter_after_write(0x80);
+        assert_eq!(
+            run(&mut counter, 3 + 37282 + 7457),
+            [
+                (3, FrameClock::Half),
+                (3 + 7457, FrameClock::Quarter),
+                (3 + 14913, FrameClock::Half),
+                (3 + 22371, FrameClock::Quarter),
+                (3 + 37281, FrameClock::Half),
+                (3 + 37282 + 7457, FrameClock::Quarter),
+            ]
+        );
+        assert!(!counter.irq);
+    }
+
+    #[test]
+    fn irq_is_asserted_on_three_cycles() {
+        let mut counter = counter_after_write(0x00);
+        let mut asserted = vec![];
+        for cycle in 1..=3 + 29830 + 100 {
+            counter.tick();
+            if std::mem::take(&mut counter.irq) {
+                asserted.push(cycle);
+            }
+        }
+        assert_eq!(asserted, [3 + 29828, 3 + 29829, 3 + 29830]);
+    }
+
+    #[test]
+    fn reset_is_delayed_by_three_or_four_cycles() {
+        let mut counter = FrameCounter::new(Region::NTSC);
+        counter.write(0x80, true);
+        assert_eq!(run(&mut counter, 4), [(3, FrameClock::Half)]);
+
+        let mut counter = FrameCounter::new(Region::NTSC);
+        counter.write(0x80, false);
+        assert_eq!(run(&mut counter, 4), [(4, FrameClock::Half)]);
+    }
+
+    #[test]
+    fn irq_inhibit_blocks_and_clears_the_irq() {
+        let mut counter = counter_after_write(0x00);
+        run(&mut counter, 3 + 29829);
+        assert!(counter.irq);
+
+        counter.write(0x40, true);
+        assert!(!counter.irq);
+        run(&mut counter, 2 * 29830);
+        assert!(!counter.irq);
+    }
+}
@@ -0,0 +1,55 @@
+#[rustfmt::skip]
+const LENGTH_TABLE: [u8; 32] = [
+    10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14,
+    12, 16, 24, 18, 48, 20, 96, 22, 192, 24, 72, 26, 16, 28, 32, 30,
+];
+
+/// Silences its channel once a note's duration, counted in half frames,
+/// runs out.
+pub struct LengthCounter {
+    enabled: bool,
+    halted: bool,
+    value: u8,
+}
+
+impl LengthCounter {
+    pub fn new() -> Self {
+        LengthCounter {
+            enabled: false,
+            halted: false,
+            value: 0,
+        }
+    }
+
+    /// Driven by the channel's enable bit in $4015; disabling clears the
+    /// counter immediately.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.value = 0;
+        }
+    }
+
+    pub fn set_halted(&mut self, halted: bool) {
+        self.halted = halted;
+    }
+
+    /// Loads the counter from the `LLLLL` bits of the channel's last
+    /// register; ignored while the channel is disabled.
+    pub fn load(&mut self, data: u8) {
+        if self.enabled {
+            self.value = LENGTH_TABLE[(data >> 3) as usize];
+        }
+    }
+
+    /// Clocked every half frame.
+    pub fn clock(&mut self) {
+        if !self.halted && self.value > 0 {
+            self.value -= 1;
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.value > 0
+    }
+}
@@ -0,0 +1,197 @@
+use self::{
+    dmc::Dmc,
+    frame_counter::{FrameClock, FrameCounter},
+    noise::Noise,
+    pulse::Pulse,
+    triangle::Triangle,
+};
+use crate::region::Region;
+
+pub mod dmc;
+pub mod envelope;
+pub mod frame_counter;
+pub mod length_counter;
+pub mod noise;
+pub mod pulse;
+pub mod sweep;
+pub mod triangle;
+
+#[allow(clippy::upper_case_acronyms)]
+pub struct APU {
+    pulse1: Pulse,
+    pulse2: Pulse,
+    triangle: Triangle,
+    noise: Noise,
+    dmc: Dmc,
+    frame_counter: FrameCounter,
+    cycles: usize,
+}
+
+impl APU {
+    pub fn new(region: Region) -> Self {
+        APU {
+            pulse1: Pulse::new(true),
+            pulse2: Pulse::new(false),
+            triangle: Triangle::new(),
+            noise: Noise::new(region),
+            dmc: Dmc::new(region),
+            frame_counter: FrameCounter::new(region),
+            cycles: 0,
+        }
+    }
+
+    pub fn tick(&mut self, cycles: u8) {
+        for _ in 0..cycles {
+            self.step_cycle();
+        }
+    }
+
+    fn step_cycle(&mut self) {
+        // The triangle and DMC timers run at the CPU clock; the others run
+        // at the APU clock, half of it.
+        self.triangle.clock_timer();
+        self.dmc.clock_timer();
+        if self.cycles % 2 == 1 {
+            self.pulse1.clock_timer();
+            self.pulse2.clock_timer();
+            self.noise.clock_timer();
+        }
+
+        match self.frame_counter.tick() {
+            Some(FrameClock::Quarter) => self.clock_quarter_frame(),
+            Some(FrameClock::Half) => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+            }
+            None => {}
+        }
+
+        self.cycles += 1;
+    }
+
+    fn clock_quarter_frame(&mut self) {
+        self.pulse1.envelope.clock();
+        self.pulse2.envelope.clock();
+        self.triangle.clock_linear_counter();
+        self.noise.envelope.clock();
+    }
+
+    fn clock_half_frame(&mut self) {
+        self.pulse1.length_counter.clock();
+        self.pulse2.length_counter.clock();
+        self.triangle.length_counter.clock();
+        self.noise.length_counter.clock();
+        self.pulse1.clock_sweep();
+        self.pulse2.clock_sweep();
+    }
+
+    pub fn write_register(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x4000..=0x4003 => self.pulse1.write_register(addr - 0x4000, data),
+            0x4004..=0x4007 => self.pulse2.write_register(addr - 0x4004, data),
+            0x4008..=0x400B => self.triangle.write_register(addr - 0x4008, data),
+            0x400C..=0x400F => self.noise.write_register(addr - 0x400C, data),
+            0x4010..=0x4013 => self.dmc.write_register(addr - 0x4010, data),
+            0x4015 => {
+                self.pulse1.length_counter.set_enabled(data & 0b0001 != 0);
+                self.pulse2.length_counter.set_enabled(data & 0b0010 != 0);
+                self.triangle.length_counter.set_enabled(data & 0b0100 != 0);
+                self.noise.length_counter.set_enabled(data & 0b1000 != 0);
+                self.dmc.set_enabled(data & 0b1_0000 != 0);
+                self.dmc.irq = false;
+            }
+            0x4017 => self.frame_counter.write(data, self.cycles % 2 == 1),
+            _ => unreachable!("{:04X} is not an APU register", addr),
+        }
+    }
+
+    /// Reads $4015: which channels are still playing, and the frame and DMC
+    /// interrupt flags. Reading acknowledges the frame interrupt. Bit 5 is
+    /// left for the bus to fill with open bus.
+    pub fn read_status(&mut self) -> u8 {
+        let status = self.peek_status();
+        self.frame_counter.irq = false;
+        status
+    }
+
+    /// Returns what reading $4015 would, without acknowledging anything.
+    pub fn peek_status(&self) -> u8 {
+        let mut status = 0;
+        let active = [
+            self.pulse1.length_counter.is_active(),
+            self.pulse2.length_counter.is_active(),
+            self.triangle.length_counter.is_active(),
+            self.noise.length_counter.is_active(),
+            self.dmc.is_active(),
+        ];
+        for (bit, active) in active.iter().enumerate() {
+            if *active {
+                status |= 1 << bit;
+            }
+        }
+        if self.frame_counter.irq {
+            status |= 0b0100_0000;
+        }
+        if self.dmc.irq {
+            status |= 0b1000_0000;
+        }
+        status
+    }
+
+    /// The address of the sample byte the DMC is waiting for, if any. The
+    /// bus must fetch it with `fill_dmc_sample_buffer`, stalling the CPU.
+    pub fn dmc_dma_request(&self) -> Option<u16> {
+        self.dmc.dma_request()
+    }
+
+    pub fn fill_dmc_sample_buffer(&mut self, data: u8) {
+        self.dmc.fill_sample_buffer(data);
+    }
+
+    pub fn irq(&self) -> bool {
+        self.frame_counter.irq || self.dmc.irq
+    }
+
+    /// The mixed output level, from 0.0 to 1.0, using the non-linear
+    /// mixer approximation.
+    #[allow(dead_code)]
+    pub fn output(&self) -> f32 {
+        let pulse = (self.pulse1.output() + self.pulse2.output()) as f32;
+        let pulse_out = if pulse == 0.0 {
+            0.0
+        } else {
+            95.88 / (8128.0 / pulse + 100.0)
+        };
+
+        let tnd = self.triangle.output() as f32 / 8227.0
+            + self.noise.output() as f32 / 12241.0
+            + self.dmc.output() as f32 / 22638.0;
+        let tnd_out = if tnd == 0.0 {
+            0.0
+        } else {
+            159.79 / (1.0 / tnd + 100.0)
+        };
+
+        pulse_out + tnd_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_read_acknowledges_frame_irq() {
+        let mut apu = APU::new(Region::NTSC);
+        apu.write_register(0x4017, 0x00);
+        for _ in 0..3 + 29829 {
+            apu.tick(1);
+        }
+        assert!(apu.irq());
+        assert_eq!(apu.peek_status() & 0b0100_0000, 0b0100_0000);
+
+        assert_eq!(apu.read_status() & 0b0100_0000, 0b0100_0000);
+        assert!(!apu.irq());
+        assert_eq!(apu.read_status() & 0b0100_0000, 0);
+    }
+}
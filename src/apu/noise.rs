@@ -0,0 +1,79 @@
+use super::{envelope::Envelope, length_counter::LengthCounter};
+use crate::region::Region;
+
+// Timer periods in APU cycles.
+const NTSC_PERIODS: [u16; 16] = [
+    2, 4, 8, 16, 32, 48, 64, 80, 101, 127, 190, 254, 381, 508, 1017, 2034,
+];
+const PAL_PERIODS: [u16; 16] = [
+    2, 4, 7, 15, 30, 44, 59, 74, 94, 118, 177, 236, 354, 472, 945, 1889,
+];
+
+pub struct Noise {
+    periods: &'static [u16; 16],
+    timer_period: u16,
+    timer: u16,
+    // Short mode taps bit 6 instead of bit 1, giving a 93-step sequence.
+    short_mode: bool,
+    shift_register: u16,
+    pub envelope: Envelope,
+    pub length_counter: LengthCounter,
+}
+
+impl Noise {
+    pub fn new(region: Region) -> Self {
+        let periods = match region {
+            Region::NTSC | Region::DENDY => &NTSC_PERIODS,
+            Region::PAL => &PAL_PERIODS,
+        };
+        Noise {
+            periods,
+            timer_period: periods[0],
+            timer: 0,
+            short_mode: false,
+            shift_register: 1,
+            envelope: Envelope::new(),
+            length_counter: LengthCounter::new(),
+        }
+    }
+
+    /// Writes one of the channel's registers, `index` 0-3; index 1 ($400D)
+    /// is unused.
+    pub fn write_register(&mut self, index: u16, data: u8) {
+        match index {
+            0 => {
+                self.length_counter.set_halted(data & 0b0010_0000 != 0);
+                self.envelope.write(data);
+            }
+            1 => {}
+            2 => {
+                self.short_mode = data & 0b1000_0000 != 0;
+                self.timer_period = self.periods[(data & 0b1111) as usize];
+            }
+            3 => {
+                self.length_counter.load(data);
+                self.envelope.restart();
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Clocked every APU cycle, i.e. every other CPU cycle.
+    pub fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period - 1;
+            let tap = if self.short_mode { 6 } else { 1 };
+            let feedback = (self.shift_register ^ (self.shift_register >> tap)) & 1;
+            self.shift_register = (self.shift_register >> 1) | (feedback << 14);
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        if self.shift_register & 1 != 0 || !self.length_counter.is_active() {
+            return 0;
+        }
+        self.envelope.output()
+    }
+}
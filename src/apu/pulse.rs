@@ -0,0 +1,77 @@
+use super::{envelope::Envelope, length_counter::LengthCounter, sweep::Sweep};
+
+const DUTY_TABLE: [[u8; 8]; 4] = [
+    [0, 1, 0, 0, 0, 0, 0, 0],
+    [0, 1, 1, 0, 0, 0, 0, 0],
+    [0, 1, 1, 1, 1, 0, 0, 0],
+    [1, 0, 0, 1, 1, 1, 1, 1],
+];
+
+pub struct Pulse {
+    duty: u8,
+    sequence_pos: u8,
+    timer_period: u16,
+    timer: u16,
+    pub envelope: Envelope,
+    pub length_counter: LengthCounter,
+    sweep: Sweep,
+}
+
+impl Pulse {
+    /// `ones_complement` selects pulse 1's sweep negation.
+    pub fn new(ones_complement: bool) -> Self {
+        Pulse {
+            duty: 0,
+            sequence_pos: 0,
+            timer_period: 0,
+            timer: 0,
+            envelope: Envelope::new(),
+            length_counter: LengthCounter::new(),
+            sweep: Sweep::new(ones_complement),
+        }
+    }
+
+    /// Writes one of the channel's four registers, `index` 0-3.
+    pub fn write_register(&mut self, index: u16, data: u8) {
+        match index {
+            0 => {
+                self.duty = data >> 6;
+                self.length_counter.set_halted(data & 0b0010_0000 != 0);
+                self.envelope.write(data);
+            }
+            1 => self.sweep.write(data),
+            2 => self.timer_period = (self.timer_period & 0xFF00) | data as u16,
+            3 => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((data as u16 & 0b111) << 8);
+                self.length_counter.load(data);
+                self.sequence_pos = 0;
+                self.envelope.restart();
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Clocked every APU cycle, i.e. every other CPU cycle.
+    pub fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            self.sequence_pos = (self.sequence_pos + 1) % 8;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn clock_sweep(&mut self) {
+        self.sweep.clock(&mut self.timer_period);
+    }
+
+    pub fn output(&self) -> u8 {
+        if !self.length_counter.is_active()
+            || DUTY_TABLE[self.duty as usize][self.sequence_pos as usize] == 0
+            || self.sweep.is_muting(self.timer_period)
+        {
+            return 0;
+        }
+        self.envelope.output()
+    }
+}
@@ -0,0 +1,65 @@
+/// The pulse channels' sweep unit, which periodically bends the channel's
+/// timer period up or down.
+pub struct Sweep {
+    enabled: bool,
+    period: u8,
+    negate: bool,
+    shift: u8,
+    divider: u8,
+    reload: bool,
+    // Pulse 1 negates with one's complement, pulse 2 with two's complement.
+    ones_complement: bool,
+}
+
+impl Sweep {
+    pub fn new(ones_complement: bool) -> Self {
+        Sweep {
+            enabled: false,
+            period: 0,
+            negate: false,
+            shift: 0,
+            divider: 0,
+            reload: false,
+            ones_complement,
+        }
+    }
+
+    /// Takes the `EPPP NSSS` bits written to $4001/$4005.
+    pub fn write(&mut self, data: u8) {
+        self.enabled = data & 0b1000_0000 != 0;
+        self.period = (data >> 4) & 0b111;
+        self.negate = data & 0b0000_1000 != 0;
+        self.shift = data & 0b0000_0111;
+        self.reload = true;
+    }
+
+    pub fn target_period(&self, timer_period: u16) -> u16 {
+        let change = timer_period >> self.shift;
+        if !self.negate {
+            timer_period + change
+        } else if self.ones_complement {
+            timer_period.saturating_sub(change + 1)
+        } else {
+            timer_period.saturating_sub(change)
+        }
+    }
+
+    /// The channel is muted while its period is too low or the sweep
+    /// target overflows, whether or not the sweep is enabled.
+    pub fn is_muting(&self, timer_period: u16) -> bool {
+        timer_period < 8 || self.target_period(timer_period) > 0x7FF
+    }
+
+    /// Clocked every half frame.
+    pub fn clock(&mut self, timer_period: &mut u16) {
+        if self.divider == 0 && self.enabled && self.shift != 0 && !self.is_muting(*timer_period) {
+            *timer_period = self.target_period(*timer_period);
+        }
+        if self.divider == 0 || self.reload {
+            self.divider = self.period;
+            self.reload = false;
+        } else {
+            self.divider -= 1;
+        }
+    }
+}
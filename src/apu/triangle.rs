@@ -0,0 +1,85 @@
+use super::length_counter::LengthCounter;
+
+#[rustfmt::skip]
+const SEQUENCE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+];
+
+pub struct Triangle {
+    sequence_pos: u8,
+    timer_period: u16,
+    timer: u16,
+    // The control flag doubles as the length counter halt flag.
+    control: bool,
+    linear_reload_value: u8,
+    linear_counter: u8,
+    linear_reload: bool,
+    pub length_counter: LengthCounter,
+}
+
+impl Triangle {
+    pub fn new() -> Self {
+        Triangle {
+            sequence_pos: 0,
+            timer_period: 0,
+            timer: 0,
+            control: false,
+            linear_reload_value: 0,
+            linear_counter: 0,
+            linear_reload: false,
+            length_counter: LengthCounter::new(),
+        }
+    }
+
+    /// Writes one of the channel's registers, `index` 0-3; index 1 ($4009)
+    /// is unused.
+    pub fn write_register(&mut self, index: u16, data: u8) {
+        match index {
+            0 => {
+                self.control = data & 0b1000_0000 != 0;
+                self.length_counter.set_halted(self.control);
+                self.linear_reload_value = data & 0b0111_1111;
+            }
+            1 => {}
+            2 => self.timer_period = (self.timer_period & 0xFF00) | data as u16,
+            3 => {
+                self.timer_period = (self.timer_period & 0x00FF) | ((data as u16 & 0b111) << 8);
+                self.length_counter.load(data);
+                self.linear_reload = true;
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Clocked every CPU cycle. The sequencer only moves while both
+    /// counters are non-zero; ultrasonic periods below 2 are held still
+    /// rather than producing a pop-inducing average level.
+    pub fn clock_timer(&mut self) {
+        if self.timer == 0 {
+            self.timer = self.timer_period;
+            if self.linear_counter > 0 && self.length_counter.is_active() && self.timer_period >= 2
+            {
+                self.sequence_pos = (self.sequence_pos + 1) % 32;
+            }
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    /// Clocked every quarter frame.
+    pub fn clock_linear_counter(&mut self) {
+        if self.linear_reload {
+            self.linear_counter = self.linear_reload_value;
+        } else if self.linear_counter > 0 {
+            self.linear_counter -= 1;
+        }
+        if !self.control {
+            self.linear_reload = false;
+        }
+    }
+
+    pub fn output(&self) -> u8 {
+        SEQUENCE[self.sequence_pos as usize]
+    }
+}
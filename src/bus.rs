@@ -1,26 +1,180 @@
-use crate::{cart::Rom, cpu::Mem, ppu::PPU};
-use core::panic;
+use crate::{
+    apu::APU,
+    cart::Rom,
+    cpu::Mem,
+    error::{Access, EmuError, ErrorPolicies, ErrorPolicy},
+    joypad::Joypad,
+    ppu::PPU,
+    region::Region,
+};
+
+const DMC_DMA_CYCLES: u8 = 4;
 
 pub struct Bus {
     ram: [u8; 2048],
     prg_rom: Vec<u8>,
-    ppu: PPU,
+    pub ppu: PPU,
+    pub apu: APU,
+    pub joypad1: Joypad,
+    pub joypad2: Joypad,
+    pub cycles: usize,
+    region: Region,
+    ppu_clock_remainder: u16,
+    irq_line: bool,
+    // Set when a DMC fetch stalled the CPU just before its next access. Only
+    // a read that follows straight away sees it; any other access clears it.
+    dmc_halted_read: bool,
+    open_bus: u8,
+    pub error_policies: ErrorPolicies,
+    error: Option<EmuError>,
 }
 
 impl Bus {
     pub fn new(rom: Rom) -> Bus {
-        let ppu = PPU::new(rom.chr_rom, rom.screen_mirroring);
+        let ppu = PPU::new(rom.chr_rom, rom.chr_ram, rom.screen_mirroring, rom.region);
         Bus {
             ram: [0; 2048],
             prg_rom: rom.prg_rom,
             ppu,
+            apu: APU::new(rom.region),
+            joypad1: Joypad::new(),
+            joypad2: Joypad::new(),
+            cycles: 0,
+            region: rom.region,
+            ppu_clock_remainder: 0,
+            irq_line: false,
+            dmc_halted_read: false,
+            open_bus: 0,
+            error_policies: ErrorPolicies::new(),
+            error: None,
+        }
+    }
+
+    /// CPU cycles per second on the cartridge's region.
+    pub fn cpu_clock_rate(&self) -> u32 {
+        self.region.cpu_clock_rate()
+    }
+
+    pub fn tick(&mut self, cycles: u8) {
+        self.cycles += cycles as usize;
+        self.apu.tick(cycles);
+
+        let (dots, per_cycles) = self.region.ppu_clock_ratio();
+        self.ppu_clock_remainder += cycles as u16 * dots;
+        self.ppu.tick(self.ppu_clock_remainder / per_cycles);
+        self.ppu_clock_remainder %= per_cycles;
+
+        // The CPU is halted while the DMC fetches its next sample byte.
+        if let Some(addr) = self.apu.dmc_dma_request() {
+            let value = self.read_prg_rom(addr);
+            self.apu.fill_dmc_sample_buffer(value);
+            self.dmc_halted_read = true;
+            self.tick(DMC_DMA_CYCLES);
+        }
+    }
+
+    pub fn poll_nmi_status(&mut self) -> bool {
+        self.ppu.poll_nmi_interrupt()
+    }
+
+    pub fn poll_irq_status(&self) -> bool {
+        self.irq_line || self.apu.irq()
+    }
+
+    #[allow(dead_code)]
+    pub fn set_irq_line(&mut self, active: bool) {
+        self.irq_line = active;
+    }
+
+    /// Applies the configured policy to `error`; halting errors are kept
+    /// until `take_error` collects them.
+    pub fn report(&mut self, error: EmuError) -> ErrorPolicy {
+        let policy = self.error_policies.policy_for(&error);
+        match policy {
+            ErrorPolicy::Halt => {
+                if self.error.is_none() {
+                    self.error = Some(error);
+                }
+            }
+            ErrorPolicy::Log => eprintln!("{}", error),
+            ErrorPolicy::OpenBus => {}
+        }
+        policy
+    }
+
+    pub fn take_error(&mut self) -> Option<EmuError> {
+        self.error.take()
+    }
+
+    // Copies a 256-byte CPU page into OAM. The CPU is halted for one cycle,
+    // plus one more to align on an odd cycle, then each byte takes a read
+    // cycle and a write cycle: 513 or 514 cycles in total.
+    fn oam_dma(&mut self, page: u8) {
+        self.tick(if self.cycles % 2 == 1 { 2 } else { 1 });
+
+        let base = (page as u16) << 8;
+        for offset in 0..=0xFF {
+            self.tick(1);
+            let value = self.mem_read(base + offset);
+            self.tick(1);
+            self.ppu.write_to_oam_data(value);
+        }
+    }
+
+    fn illegal_read(&mut self, addr: u16) -> u8 {
+        self.report(EmuError::IllegalBusAccess {
+            addr,
+            access: Access::Read,
+        });
+        self.open_bus
+    }
+
+    fn illegal_write(&mut self, addr: u16) {
+        self.report(EmuError::IllegalBusAccess {
+            addr,
+            access: Access::Write,
+        });
+    }
+
+    // The DMC halts the CPU on a read cycle, which it repeats while halted.
+    // Repeating a controller read clocks its shift register an extra time,
+    // so a button is skipped. This is only modelled when the CPU is cycle
+    // stepped: otherwise the bus is clocked after the instruction has run,
+    // so no DMC fetch ever lands just before the controller read.
+    fn read_joypad(&mut self, addr: u16, dmc_halted: bool) -> u8 {
+        let joypad = if addr == 0x4016 {
+            &mut self.joypad1
+        } else {
+            &mut self.joypad2
+        };
+        if dmc_halted {
+            joypad.read();
+        }
+        joypad.read() | (self.open_bus & 0b1110_0000)
+    }
+
+    /// Returns what reading `addr` would, without any of the side effects:
+    /// no register is acknowledged or advanced, no joypad is clocked and
+    /// illegal accesses are not reported. For debugging tools such as `trace`.
+    pub fn peek(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x1FFF => {
+                let mirror_down_addr = addr & 0b00000111_11111111;
+                self.ram[mirror_down_addr as usize]
+            }
+            0x2000..=0x3FFF => self.ppu.peek_register(addr & 0b00100000_00000111),
+            0x4015 => self.apu.peek_status() | (self.open_bus & 0b0010_0000),
+            0x4016 => self.joypad1.peek() | (self.open_bus & 0b1110_0000),
+            0x4017 => self.joypad2.peek() | (self.open_bus & 0b1110_0000),
+            0x8000..=0xFFFF => self.read_prg_rom(addr),
+            _ => self.open_bus,
         }
     }
 
     fn read_prg_rom(&self, mut addr: u16) -> u8 {
         addr -= 0x8000;
         if self.prg_rom.len() == 0x4000 && addr >= 0x4000 {
-            addr = addr % 0x4000;
+            addr %= 0x4000;
         }
         self.prg_rom[addr as usize]
     }
@@ -28,32 +182,38 @@ impl Bus {
 
 impl Mem for Bus {
     fn mem_read(&mut self, addr: u16) -> u8 {
-        match addr {
+        let dmc_halted = std::mem::take(&mut self.dmc_halted_read);
+        let value = match addr {
             0x0000..=0x1FFF => {
                 let mirror_down_addr = addr & 0b00000111_11111111;
                 self.ram[mirror_down_addr as usize]
             }
-            0x2000 | 0x2001 | 0x2003 | 0x2005 | 0x2006 => {
-                panic!("Attempt to read from write-only PPU address {:X}", addr)
-            }
+            0x2000 | 0x2001 | 0x2003 | 0x2005 | 0x2006 => self.ppu.read_open_bus(),
             0x2002 => self.ppu.read_status(),
-            0x2004 => {
-                panic!("Attempt to read from OAM data")
-            }
-            0x2007 => self.ppu.read_data(),
+            0x2004 => self.ppu.read_oam_data(),
+            0x2007 => match self.ppu.read_data() {
+                Ok(value) => value,
+                Err(error) => {
+                    self.report(error);
+                    self.open_bus
+                }
+            },
             0x2008..=0x3FFF => {
                 let mirror_down_addr = addr & 0b00100000_00000111;
                 self.mem_read(mirror_down_addr)
             }
+            0x4015 => self.apu.read_status() | (self.open_bus & 0b0010_0000),
+            0x4016 | 0x4017 => self.read_joypad(addr, dmc_halted),
             0x8000..=0xFFFF => self.read_prg_rom(addr),
-            _ => {
-                println!("Ignoring mem access at {}", addr);
-                0
-            }
-        }
+            _ => self.illegal_read(addr),
+        };
+        self.open_bus = value;
+        value
     }
 
     fn mem_write(&mut self, addr: u16, value: u8) {
+        self.dmc_halted_read = false;
+        self.open_bus = value;
         match addr {
             0x0000..=0x1FFF => {
                 let mirror_down_addr = addr & 0b11111111111;
@@ -66,33 +226,109 @@ impl Mem for Bus {
                 self.ppu.write_to_mask(value);
             }
             0x2002 => {
-                panic!("Attempt to write from read-only PPU address {:X}", addr)
+                self.ppu.write_to_status(value);
             }
             0x2003 => {
-                panic!("Attempt to write to OAM address register")
+                self.ppu.write_to_oam_addr(value);
             }
             0x2004 => {
-                panic!("Attempt to write to OAM data register")
+                self.ppu.write_to_oam_data(value);
             }
             0x2005 => {
-                panic!("Attempt to write to PPU scroll register")
+                self.ppu.write_to_scroll(value);
             }
             0x2006 => {
                 self.ppu.write_to_ppu_addr(value);
             }
             0x2007 => {
-                self.ppu.write_to_data(value);
+                if let Err(error) = self.ppu.write_to_data(value) {
+                    self.report(error);
+                }
             }
             0x2008..=0x3FFF => {
-                let _mirror_down_addr = addr & 0b00100000_00000111;
-                self.mem_write(addr, value);
-            }
-            0x8000..=0xFFFF => {
-                panic!("Attempt to write to Cartridge ROM space")
+                let mirror_down_addr = addr & 0b00100000_00000111;
+                self.mem_write(mirror_down_addr, value);
             }
-            _ => {
-                println!("Ignoring mem write-access at {}", addr);
+            0x4000..=0x4013 | 0x4015 | 0x4017 => self.apu.write_register(addr, value),
+            0x4014 => self.oam_dma(value),
+            0x4016 => {
+                self.joypad1.write(value);
+                self.joypad2.write(value);
             }
+            _ => self.illegal_write(addr),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cart::Mirroring, joypad::Joypad};
+
+    fn test_bus() -> Bus {
+        Bus::new(Rom {
+            prg_rom: vec![0; 0x4000],
+            chr_rom: vec![0; 0x2000],
+            chr_ram: false,
+            mapper: 0,
+            screen_mirroring: Mirroring::HORIZONTAL,
+            region: Region::NTSC,
+        })
+    }
+
+    #[test]
+    fn oam_attribute_bytes_drop_unimplemented_bits() {
+        let mut bus = test_bus();
+        bus.mem_write(0x2003, 0x00);
+        for _ in 0..4 {
+            bus.mem_write(0x2004, 0xFF);
+        }
+        let oam: Vec<u8> = (0..4)
+            .map(|offset| {
+                bus.mem_write(0x2003, offset);
+                bus.mem_read(0x2004)
+            })
+            .collect();
+        assert_eq!(oam, [0xFF, 0xFF, 0xE3, 0xFF]);
+
+        for offset in 0..0x100 {
+            bus.mem_write(0x0200 + offset, 0xFF);
+        }
+        bus.mem_write(0x2003, 0x00);
+        bus.mem_write(0x4014, 0x02);
+        for (index, value) in bus.ppu.oam_data.iter().enumerate() {
+            let expected = if index % 4 == 2 { 0xE3 } else { 0xFF };
+            assert_eq!(*value, expected, "OAM byte {:02X}", index);
+        }
+    }
+
+    // Strobes a controller holding only B, then starts a one-byte DMC
+    // sample, which requests its DMA fetch straight away.
+    fn bus_with_pending_dmc_fetch() -> Bus {
+        let mut bus = test_bus();
+        bus.joypad1
+            .set_button_pressed_status(Joypad::BUTTON_B, true);
+        bus.mem_write(0x4016, 1);
+        bus.mem_write(0x4016, 0);
+        bus.mem_write(0x4015, 0b1_0000);
+        bus
+    }
+
+    #[test]
+    fn dmc_fetch_before_joypad_read_skips_a_button() {
+        let mut bus = bus_with_pending_dmc_fetch();
+        // A cycle-stepped CPU clocks the bus just before each access.
+        bus.tick(1);
+        assert_eq!(bus.mem_read(0x4016) & 1, 1, "A is skipped and B read");
+        assert_eq!(bus.mem_read(0x4016) & 1, 0, "select");
+    }
+
+    #[test]
+    fn dmc_fetch_before_a_write_leaves_joypad_reads_alone() {
+        let mut bus = bus_with_pending_dmc_fetch();
+        bus.tick(1);
+        bus.mem_write(0x0000, 0);
+        assert_eq!(bus.mem_read(0x4016) & 1, 0, "A");
+        assert_eq!(bus.mem_read(0x4016) & 1, 1, "B");
+    }
+}
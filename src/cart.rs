@@ -1,9 +1,12 @@
+use crate::{error::EmuError, region::Region};
+
 const NES_TAG: [u8; 4] = [0x4E, 0x45, 0x53, 0x1A];
 const PRG_ROM_PAGE_SIZE: usize = 16384;
 const CHR_ROM_PAGE_SIZE: usize = 8192;
+const CHR_RAM_SIZE: usize = 8192;
 
 #[derive(Debug, PartialEq)]
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum Mirroring {
     VERTICAL,
     HORIZONTAL,
@@ -13,22 +16,32 @@ pub enum Mirroring {
 pub struct Rom {
     pub prg_rom: Vec<u8>,
     pub chr_rom: Vec<u8>,
+    /// Set when the cartridge has no CHR-ROM, in which case `chr_rom` is
+    /// writable CHR-RAM.
+    pub chr_ram: bool,
+    #[allow(dead_code)]
     pub mapper: u8,
     pub screen_mirroring: Mirroring,
+    /// Taken from the NES 2.0 header, NTSC otherwise. Change it before
+    /// building the `Bus` to run the cartridge on another region.
+    pub region: Region,
 }
 
 impl Rom {
-    pub fn new(raw: &Vec<u8>) -> Result<Rom, String> {
-        if &raw[0..4] != NES_TAG {
-            return Err("File is not in iNES file format".to_string());
+    pub fn new(raw: &[u8]) -> Result<Rom, EmuError> {
+        if raw.len() < 16 || raw[0..4] != NES_TAG {
+            return Err(EmuError::BadRom(
+                "File is not in iNES file format".to_string(),
+            ));
         }
 
         let mapper = (raw[7] & 0b1111_0000) | (raw[6] >> 4);
 
-        let ines_ver = (raw[7] >> 2) & 0b11;
-        if ines_ver != 0 {
-            return Err("NES2.0 format is not supported".to_string());
-        }
+        let nes2 = match (raw[7] >> 2) & 0b11 {
+            0 => false,
+            2 => true,
+            _ => return Err(EmuError::BadRom("Unknown iNES header version".to_string())),
+        };
 
         let four_screen = raw[6] & 0b1000 != 0;
         let vertical_mirroring = raw[6] & 0b1 != 0;
@@ -38,19 +51,64 @@ impl Rom {
             (false, false) => Mirroring::HORIZONTAL,
         };
 
-        let prg_rom_size = raw[4] as usize * PRG_ROM_PAGE_SIZE;
-        let chr_rom_size = raw[5] as usize * CHR_ROM_PAGE_SIZE;
+        let (prg_rom_pages, chr_rom_pages) = if nes2 {
+            if raw[9] & 0x0F == 0x0F || raw[9] & 0xF0 == 0xF0 {
+                return Err(EmuError::BadRom(
+                    "NES2.0 exponent-multiplier ROM sizes are not supported".to_string(),
+                ));
+            }
+            (
+                (raw[9] as usize & 0x0F) << 8 | raw[4] as usize,
+                (raw[9] as usize & 0xF0) << 4 | raw[5] as usize,
+            )
+        } else {
+            (raw[4] as usize, raw[5] as usize)
+        };
+        if prg_rom_pages == 0 {
+            return Err(EmuError::BadRom("Header declares no PRG-ROM".to_string()));
+        }
+        let prg_rom_size = prg_rom_pages * PRG_ROM_PAGE_SIZE;
+        let chr_rom_size = chr_rom_pages * CHR_ROM_PAGE_SIZE;
+
+        // NES 2.0 gives the CHR-RAM size as a shift count; plain iNES
+        // implies 8KiB of CHR-RAM when there is no CHR-ROM.
+        let chr_ram_shift = raw[11] & 0x0F;
+        let chr_ram_size = if nes2 && chr_ram_shift != 0 {
+            (64 << chr_ram_shift).max(CHR_RAM_SIZE)
+        } else {
+            CHR_RAM_SIZE
+        };
+
+        let region = match raw[12] & 0b11 {
+            1 if nes2 => Region::PAL,
+            3 if nes2 => Region::DENDY,
+            _ => Region::NTSC,
+        };
 
         let skip_trainer = raw[6] & 0b100 != 0;
 
         let prg_rom_start = 16 + if skip_trainer { 512 } else { 0 };
         let chr_rom_start = prg_rom_start + prg_rom_size;
+        if raw.len() < chr_rom_start + chr_rom_size {
+            return Err(EmuError::BadRom(
+                "File is shorter than its header declares".to_string(),
+            ));
+        }
+
+        let chr_ram = chr_rom_size == 0;
+        let chr_rom = if chr_ram {
+            vec![0; chr_ram_size]
+        } else {
+            raw[chr_rom_start..(chr_rom_start + chr_rom_size)].to_vec()
+        };
 
         Ok(Rom {
             prg_rom: raw[prg_rom_start..(prg_rom_start + prg_rom_size)].to_vec(),
-            chr_rom: raw[chr_rom_start..(chr_rom_start + chr_rom_size)].to_vec(),
+            chr_rom,
+            chr_ram,
             mapper,
             screen_mirroring,
+            region,
         })
     }
 }
@@ -1,5 +1,8 @@
-use crate::{bus::Bus, opcodes};
-use std::collections::HashMap;
+use crate::{
+    bus::Bus,
+    error::{EmuError, ErrorPolicy},
+    opcodes::{Instruction, OPCODES_TABLE},
+};
 
 const NEGATIVE_FLAG: u8 = 0b1000_0000;
 const OVERFLOW_FLAG: u8 = 0b0100_0000;
@@ -10,8 +13,34 @@ const INTERRUPT_DISABLE_FLAG: u8 = 0b0000_0100;
 const ZERO_FLAG: u8 = 0b0000_0010;
 const CARRY_FLAG: u8 = 0b0000_0001;
 
-#[derive(Debug, PartialEq)]
-#[allow(non_camel_case_types)]
+mod interrupt {
+    pub(super) struct Interrupt {
+        pub(super) vector_addr: u16,
+        pub(super) b_flag_mask: u8,
+        pub(super) cpu_cycles: u8,
+    }
+
+    pub(super) const NMI: Interrupt = Interrupt {
+        vector_addr: 0xFFFA,
+        b_flag_mask: 0b0010_0000,
+        cpu_cycles: 7,
+    };
+
+    pub(super) const IRQ: Interrupt = Interrupt {
+        vector_addr: 0xFFFE,
+        b_flag_mask: 0b0010_0000,
+        cpu_cycles: 7,
+    };
+
+    pub(super) const BRK: Interrupt = Interrupt {
+        vector_addr: 0xFFFE,
+        b_flag_mask: 0b0011_0000,
+        cpu_cycles: 7,
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum AddressingMode {
     Implied,
     Accumulator,
@@ -28,6 +57,25 @@ pub enum AddressingMode {
     RELATIVE,
 }
 
+/// What a call to `CPU::step` did, returned with the cycles it consumed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    /// Executed the instruction with this opcode.
+    Instruction(u8),
+    /// Entered an NMI or IRQ handler. The program counter now holds the
+    /// handler's entry address; its first instruction has not run yet.
+    Interrupt,
+}
+
+/// How the addressing-mode decoder reads operand bytes and pointers.
+#[derive(Clone, Copy, PartialEq)]
+enum Fetch {
+    /// Clocked reads, including the dummy reads, as the CPU performs them.
+    Execute,
+    /// Side-effect-free `Bus::peek`s.
+    Peek,
+}
+
 pub trait Mem {
     fn mem_read(&mut self, addr: u16) -> u8;
 
@@ -35,18 +83,20 @@ pub trait Mem {
 
     fn mem_read_u16(&mut self, pos: u16) -> u16 {
         let lo = self.mem_read(pos) as u16;
-        let hi = self.mem_read(pos + 1) as u16;
-        (hi << 8) | (lo as u16)
+        let hi = self.mem_read(pos.wrapping_add(1)) as u16;
+        (hi << 8) | lo
     }
 
+    #[allow(dead_code)]
     fn mem_write_u16(&mut self, pos: u16, value: u16) {
         let hi = (value >> 8) as u8;
         let lo = (value & 0x00FF) as u8;
         self.mem_write(pos, lo);
-        self.mem_write(pos + 1, hi);
+        self.mem_write(pos.wrapping_add(1), hi);
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 pub struct CPU {
     pub register_a: u8,
     pub register_x: u8,
@@ -55,6 +105,19 @@ pub struct CPU {
     pub stack_pointer: u8,
     pub program_counter: u16,
     pub bus: Bus,
+    /// Constant ORed into A by the unstable XAA and LAX #imm opcodes.
+    /// It differs between individual CPUs; 0xEE is the most common value.
+    pub unstable_magic: u8,
+    /// When set, every bus access, including dummy reads and writes, first
+    /// clocks the rest of the system by one CPU cycle. Otherwise the bus is
+    /// clocked once per instruction, after it has executed.
+    ///
+    /// Effects that depend on the cycle an access falls on are only modelled
+    /// when set: a $2002 read racing the start of vblank, and a DMC sample
+    /// fetch corrupting the controller read that follows it.
+    pub cycle_stepped: bool,
+    access_cycles: u8,
+    extra_cycles: u8,
 }
 
 impl Mem for CPU {
@@ -67,11 +130,6 @@ impl Mem for CPU {
     }
 
     fn mem_read_u16(&mut self, pos: u16) -> u16 {
-        if pos == 0xFF || pos == 0x02FF {
-            let lo = self.mem_read(pos);
-            let hi = self.mem_read(pos & 0xFF00);
-            return (hi as u16) << 8 | (lo as u16);
-        }
         self.bus.mem_read_u16(pos)
     }
 
@@ -89,11 +147,27 @@ impl CPU {
             status: 0x24,
             stack_pointer: 0xFD,
             program_counter: 0,
-            bus: bus,
+            bus,
+            unstable_magic: 0xEE,
+            cycle_stepped: false,
+            access_cycles: 0,
+            extra_cycles: 0,
         }
     }
 
-    pub fn get_absolute_address(&mut self, mode: &AddressingMode, addr: u16) -> u16 {
+    /// Decodes the operand address of an instruction whose operand bytes
+    /// start at `addr`, as `get_operand_address` does while executing, but
+    /// using `Bus::peek`: nothing is clocked, no dummy accesses are made and
+    /// no register sees a read. For use by debugging tools such as `trace`.
+    pub fn get_absolute_address(&mut self, mode: &AddressingMode, addr: u16) -> (u16, bool) {
+        self.decode_address(mode, addr, Fetch::Peek)
+    }
+
+    fn get_operand_address(&mut self, mode: &AddressingMode) -> (u16, bool) {
+        self.decode_address(mode, self.program_counter, Fetch::Execute)
+    }
+
+    fn decode_address(&mut self, mode: &AddressingMode, addr: u16, fetch: Fetch) -> (u16, bool) {
         match mode {
             AddressingMode::Implied => {
                 panic!("AddressingMode::Implied");
@@ -101,51 +175,153 @@ impl CPU {
             AddressingMode::Accumulator => {
                 panic!("AddressingMode::Accumulator");
             }
-            AddressingMode::Immediate => addr,
+            AddressingMode::Immediate => (addr, false),
 
-            AddressingMode::ZeroPage => self.mem_read(addr) as u16,
+            AddressingMode::ZeroPage => (self.fetch(addr, fetch) as u16, false),
 
-            AddressingMode::Absolute => self.mem_read_u16(addr),
+            AddressingMode::Absolute => (self.fetch_u16(addr, fetch), false),
 
             AddressingMode::ZeroPage_X => {
-                let pos = self.mem_read(addr);
-                pos.wrapping_add(self.register_x) as u16
+                let pos = self.fetch(addr, fetch);
+                self.fetch_dummy(pos as u16, fetch);
+                (pos.wrapping_add(self.register_x) as u16, false)
             }
             AddressingMode::ZeroPage_Y => {
-                let pos = self.mem_read(addr);
-                pos.wrapping_add(self.register_y) as u16
+                let pos = self.fetch(addr, fetch);
+                self.fetch_dummy(pos as u16, fetch);
+                (pos.wrapping_add(self.register_y) as u16, false)
             }
             AddressingMode::Absolute_X => {
-                let base = self.mem_read_u16(addr);
-                base.wrapping_add(self.register_x as u16)
+                let base = self.fetch_u16(addr, fetch);
+                let addr = base.wrapping_add(self.register_x as u16);
+                (addr, page_cross(base, addr))
             }
             AddressingMode::Absolute_Y => {
-                let base = self.mem_read_u16(addr);
-                base.wrapping_add(self.register_y as u16)
+                let base = self.fetch_u16(addr, fetch);
+                let addr = base.wrapping_add(self.register_y as u16);
+                (addr, page_cross(base, addr))
             }
             AddressingMode::Indirect => {
-                let base = self.mem_read_u16(addr);
-                self.mem_read_u16(base)
+                let base = self.fetch_u16(addr, fetch);
+                (self.fetch_u16_in_page(base, fetch), false)
             }
             AddressingMode::Indirect_X => {
-                let base = self.mem_read(addr);
-                let ptr = (base as u8).wrapping_add(self.register_x);
-                self.mem_read_u16(ptr as u16)
+                let base = self.fetch(addr, fetch);
+                self.fetch_dummy(base as u16, fetch);
+                let ptr = base.wrapping_add(self.register_x);
+                (self.fetch_u16_in_page(ptr as u16, fetch), false)
             }
             AddressingMode::Indirect_Y => {
-                let base = self.mem_read(addr);
-                let deref_base = self.mem_read_u16(base as u16);
-                deref_base.wrapping_add(self.register_y as u16)
+                let base = self.fetch(addr, fetch);
+                let deref_base = self.fetch_u16_in_page(base as u16, fetch);
+                let addr = deref_base.wrapping_add(self.register_y as u16);
+                (addr, page_cross(deref_base, addr))
             }
             AddressingMode::RELATIVE => {
-                let jump = self.mem_read(addr) as i8;
-                addr.wrapping_add(1).wrapping_add(jump as u16)
+                let jump = self.fetch(addr, fetch) as i8;
+                let base = addr.wrapping_add(1);
+                let addr = base.wrapping_add(jump as u16);
+                (addr, page_cross(base, addr))
             }
         }
     }
 
-    fn get_operand_address(&mut self, mode: &AddressingMode) -> u16 {
-        self.get_absolute_address(mode, self.program_counter)
+    fn fetch(&mut self, addr: u16, fetch: Fetch) -> u8 {
+        match fetch {
+            Fetch::Execute => self.read(addr),
+            Fetch::Peek => self.bus.peek(addr),
+        }
+    }
+
+    fn fetch_dummy(&mut self, addr: u16, fetch: Fetch) {
+        if fetch == Fetch::Execute {
+            self.dummy_read(addr);
+        }
+    }
+
+    fn fetch_u16(&mut self, pos: u16, fetch: Fetch) -> u16 {
+        let lo = self.fetch(pos, fetch) as u16;
+        let hi = self.fetch(pos.wrapping_add(1), fetch) as u16;
+        (hi << 8) | lo
+    }
+
+    // The 6502 never carries into the high byte when fetching a pointer:
+    // zero-page pointers wrap around within page zero, and JMP ($xxFF)
+    // takes its high byte from $xx00.
+    fn fetch_u16_in_page(&mut self, pos: u16, fetch: Fetch) -> u16 {
+        let lo = self.fetch(pos, fetch) as u16;
+        let hi = self.fetch(next_in_page(pos), fetch) as u16;
+        (hi << 8) | lo
+    }
+
+    // Indexed reads only spend the extra cycle, reading from the address
+    // before its high byte is fixed up, when indexing crosses a page.
+    fn get_operand_value(&mut self, mode: &AddressingMode) -> u8 {
+        let (addr, page_cross) = self.get_operand_address(mode);
+        if page_cross {
+            self.dummy_read(addr.wrapping_sub(0x100));
+            self.extra_cycles += 1;
+        }
+        self.read(addr)
+    }
+
+    // Indexed writes and read-modify-writes always spend that cycle.
+    fn get_write_address(&mut self, mode: &AddressingMode) -> (u16, bool) {
+        let (addr, page_cross) = self.get_operand_address(mode);
+        if matches!(
+            mode,
+            AddressingMode::Absolute_X | AddressingMode::Absolute_Y | AddressingMode::Indirect_Y
+        ) {
+            let unfixed_addr = if page_cross {
+                addr.wrapping_sub(0x100)
+            } else {
+                addr
+            };
+            self.dummy_read(unfixed_addr);
+        }
+        (addr, page_cross)
+    }
+
+    fn read(&mut self, addr: u16) -> u8 {
+        if self.cycle_stepped {
+            self.bus.tick(1);
+            self.access_cycles += 1;
+        }
+        self.bus.mem_read(addr)
+    }
+
+    fn write(&mut self, addr: u16, value: u8) {
+        if self.cycle_stepped {
+            self.bus.tick(1);
+            self.access_cycles += 1;
+        }
+        self.bus.mem_write(addr, value);
+    }
+
+    // Dummy accesses only matter, and are only performed, when cycle stepping.
+    fn dummy_read(&mut self, addr: u16) {
+        if self.cycle_stepped {
+            self.read(addr);
+        }
+    }
+
+    fn dummy_write(&mut self, addr: u16, value: u8) {
+        if self.cycle_stepped {
+            self.write(addr, value);
+        }
+    }
+
+    // Clocks whatever part of `cycles`, plus any penalty cycles, the bus
+    // accesses made so far have not already accounted for.
+    fn complete_cycles(&mut self, cycles: u8) {
+        let cycles = cycles + self.extra_cycles;
+        if !self.cycle_stepped {
+            self.bus.tick(cycles);
+        } else if self.access_cycles < cycles {
+            self.bus.tick(cycles - self.access_cycles);
+        }
+        self.access_cycles = 0;
+        self.extra_cycles = 0;
     }
 
     pub fn reset(&mut self) {
@@ -155,237 +331,396 @@ impl CPU {
         self.status = 0x24;
         self.stack_pointer = 0xFD;
         self.program_counter = self.mem_read_u16(0xFFFC);
+        self.bus.tick(7);
     }
 
-    pub fn run<F>(&mut self, mut callback: F)
+    /// Runs until `callback`, invoked before each instruction, returns `false`.
+    pub fn run<F>(&mut self, mut callback: F) -> Result<(), EmuError>
     where
-        F: FnMut(&mut CPU),
+        F: FnMut(&mut CPU) -> bool,
     {
-        let ref opcodes: HashMap<u8, &'static opcodes::OpCode> = *opcodes::OPCODES_MAP;
-
         loop {
-            callback(self);
-            let opscode = self.mem_read(self.program_counter);
-            self.program_counter += 1;
-            let program_counter_state = self.program_counter;
-
-            let opcode = opcodes
-                .get(&opscode)
-                .expect(&format!("OpCode {:x} is not recognized", opscode));
-
-            match opscode {
-                0x69 | 0x65 | 0x75 | 0x6D | 0x7D | 0x79 | 0x61 | 0x71 => {
-                    self.adc(&opcode.mode);
-                }
-                0xE9 | 0xE5 | 0xF5 | 0xED | 0xFD | 0xF9 | 0xE1 | 0xF1 => {
-                    self.sbc(&opcode.mode);
-                }
-                0x29 | 0x25 | 0x35 | 0x2D | 0x3D | 0x39 | 0x21 | 0x31 => {
-                    self.and(&opcode.mode);
-                }
-                0x09 | 0x05 | 0x15 | 0x0D | 0x1D | 0x19 | 0x01 | 0x11 => {
-                    self.ora(&opcode.mode);
-                }
-                0x49 | 0x45 | 0x55 | 0x4D | 0x5D | 0x59 | 0x41 | 0x51 => {
-                    self.eor(&opcode.mode);
-                }
-                0x0A | 0x06 | 0x16 | 0x0E | 0x1E => {
-                    self.asl(&opcode.mode);
-                }
-                0x4A | 0x46 | 0x56 | 0x4E | 0x5E => {
-                    self.lsr(&opcode.mode);
-                }
-                0x2A | 0x26 | 0x36 | 0x2E | 0x3E => {
-                    self.rol(&opcode.mode);
-                }
-                0x6A | 0x66 | 0x76 | 0x6E | 0x7E => {
-                    self.ror(&opcode.mode);
-                }
-                0x90 => {
-                    self.bcc(&opcode.mode);
-                }
-                0xB0 => {
-                    self.bcs(&opcode.mode);
-                }
-                0xF0 => {
-                    self.beq(&opcode.mode);
-                }
-                0xD0 => {
-                    self.bne(&opcode.mode);
-                }
-                0x30 => {
-                    self.bmi(&opcode.mode);
-                }
-                0x10 => {
-                    self.bpl(&opcode.mode);
-                }
-                0x50 => {
-                    self.bvc(&opcode.mode);
-                }
-                0x70 => {
-                    self.bvs(&opcode.mode);
-                }
-                0x24 | 0x2C => {
-                    self.bit(&opcode.mode);
-                }
-                0x4C | 0x6C => {
-                    self.jmp(&opcode.mode);
-                }
-                0x20 => {
-                    self.jsr(&opcode.mode);
-                }
-                0x60 => {
-                    self.rts();
-                }
-                0x00 => {
-                    return;
-                }
-                0x40 => {
-                    self.rti();
-                }
-                0xC9 | 0xC5 | 0xD5 | 0xCD | 0xDD | 0xD9 | 0xC1 | 0xD1 => {
-                    self.cmp(&opcode.mode);
-                }
-                0xE0 | 0xE4 | 0xEC => {
-                    self.cpx(&opcode.mode);
-                }
-                0xC0 | 0xC4 | 0xCC => {
-                    self.cpy(&opcode.mode);
-                }
-                0xC6 | 0xD6 | 0xCE | 0xDE => {
-                    self.dec(&opcode.mode);
-                }
-                0xCA => {
-                    self.dex();
-                }
-                0x88 => {
-                    self.dey();
-                }
-                0xE6 | 0xF6 | 0xEE | 0xFE => {
-                    self.inc(&opcode.mode);
-                }
-                0xE8 => {
-                    self.inx();
-                }
-                0xC8 => {
-                    self.iny();
-                }
-                0x18 => {
-                    self.clc();
-                }
-                0x38 => {
-                    self.sec();
-                }
-                0xD8 => {
-                    self.cld();
-                }
-                0xF8 => {
-                    self.sed();
-                }
-                0x58 => {
-                    self.cli();
-                }
-                0x78 => {
-                    self.sei();
-                }
-                0xB8 => {
-                    self.clv();
-                }
-                0xA9 | 0xA5 | 0xB5 | 0xAD | 0xBD | 0xB9 | 0xA1 | 0xB1 => {
-                    self.lda(&opcode.mode);
-                }
-                0xA2 | 0xA6 | 0xB6 | 0xAE | 0xBE => {
-                    self.ldx(&opcode.mode);
-                }
-                0xA0 | 0xA4 | 0xB4 | 0xAC | 0xBC => {
-                    self.ldy(&opcode.mode);
-                }
-                0x85 | 0x95 | 0x8D | 0x9D | 0x99 | 0x81 | 0x91 => {
-                    self.sta(&opcode.mode);
-                }
-                0x86 | 0x96 | 0x8E => {
-                    self.stx(&opcode.mode);
-                }
-                0x84 | 0x94 | 0x8C => {
-                    self.sty(&opcode.mode);
-                }
-                0xAA => {
-                    self.tax();
-                }
-                0xA8 => {
-                    self.tay();
-                }
-                0xBA => {
-                    self.tsx();
-                }
-                0x8A => {
-                    self.txa();
-                }
-                0x9A => {
-                    self.txs();
-                }
-                0x98 => {
-                    self.tya();
-                }
-                0x48 => {
-                    self.pha();
-                }
-                0x68 => {
-                    self.pla();
-                }
-                0x08 => {
-                    self.php();
-                }
-                0x28 => {
-                    self.plp();
-                }
-                0xEA | 0x04 | 0x44 | 0x64 | 0x14 | 0x34 | 0x54 | 0x74 | 0xD4 | 0xF4 | 0x0C
-                | 0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC | 0x1A | 0x3A | 0x5A | 0x7A | 0xDA
-                | 0xFA => {
-                    self.nop();
-                }
-                0x80 => {
-                    self.nop();
-                }
-                0xA7 | 0xB7 | 0xAF | 0xBF | 0xA3 | 0xB3 => {
-                    self.lax(&opcode.mode);
-                }
-                0x87 | 0x97 | 0x8F | 0x83 => {
-                    self.sax(&opcode.mode);
-                }
-                0xEB => {
-                    self.sbc(&opcode.mode);
-                }
-                0xC7 | 0xD7 | 0xCF | 0xDF | 0xDB | 0xD3 | 0xC3 => {
-                    self.dcp(&opcode.mode);
-                }
-                0xE7 | 0xF7 | 0xEF | 0xFF | 0xFB | 0xE3 | 0xF3 => {
-                    self.isb(&opcode.mode);
-                }
-                0x07 | 0x17 | 0x0F | 0x1F | 0x1B | 0x03 | 0x13 => {
-                    self.slo(&opcode.mode);
-                }
-                0x27 | 0x37 | 0x2F | 0x3F | 0x3B | 0x33 | 0x23 => {
-                    self.rla(&opcode.mode);
-                }
-                0x47 | 0x57 | 0x4F | 0x5F | 0x5B | 0x43 | 0x53 => {
-                    self.sre(&opcode.mode);
-                }
-                0x67 | 0x77 | 0x6F | 0x7F | 0x7B | 0x63 | 0x73 => {
-                    self.rra(&opcode.mode);
-                }
-                _ => todo!(""),
-            }
-
-            if program_counter_state == self.program_counter {
-                self.program_counter += (opcode.length - 1) as u16;
+            self.poll_interrupts();
+            if !callback(self) {
+                return Ok(());
             }
+            self.execute_instruction();
+            if let Some(error) = self.bus.take_error() {
+                return Err(error);
+            }
+        }
+    }
+
+    /// Enters a pending NMI or IRQ handler if there is one, otherwise
+    /// executes a single instruction. Either way, returns what was done and
+    /// the number of CPU cycles consumed.
+    pub fn step(&mut self) -> Result<(Step, usize), EmuError> {
+        let start = self.bus.cycles;
+        let step = if self.poll_interrupts() {
+            Step::Interrupt
+        } else {
+            Step::Instruction(self.execute_instruction())
+        };
+        match self.bus.take_error() {
+            Some(error) => Err(error),
+            None => Ok((step, self.bus.cycles - start)),
+        }
+    }
+
+    /// Runs whole instructions until at least `cycles` CPU cycles have elapsed.
+    /// Returns the number of cycles actually consumed.
+    #[allow(dead_code)]
+    pub fn run_for_cycles(&mut self, cycles: usize) -> Result<usize, EmuError> {
+        let start = self.bus.cycles;
+        while self.bus.cycles - start < cycles {
+            self.step()?;
+        }
+        Ok(self.bus.cycles - start)
+    }
+
+    /// CPU cycles per second, which depends on the cartridge's region.
+    #[allow(dead_code)]
+    pub fn clock_rate(&self) -> u32 {
+        self.bus.cpu_clock_rate()
+    }
+
+    /// Runs until the PPU finishes the current frame.
+    #[allow(dead_code)]
+    pub fn run_until_frame(&mut self) -> Result<(), EmuError> {
+        let frame = self.bus.ppu.frame_count;
+        while self.bus.ppu.frame_count == frame {
+            self.step()?;
         }
+        Ok(())
+    }
+
+    /// Runs until `condition`, checked before each instruction, returns `true`.
+    #[allow(dead_code)]
+    pub fn run_until<F>(&mut self, mut condition: F) -> Result<(), EmuError>
+    where
+        F: FnMut(&CPU) -> bool,
+    {
+        while !condition(self) {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    // Returns whether an interrupt was serviced.
+    fn poll_interrupts(&mut self) -> bool {
+        let interrupt = if self.bus.poll_nmi_status() {
+            interrupt::NMI
+        } else if self.bus.poll_irq_status() && self.status & INTERRUPT_DISABLE_FLAG == 0 {
+            interrupt::IRQ
+        } else {
+            return false;
+        };
+
+        let cycles = interrupt.cpu_cycles;
+        self.dummy_read(self.program_counter);
+        self.dummy_read(self.program_counter);
+        self.interrupt(interrupt);
+        self.complete_cycles(cycles);
+        true
+    }
+
+    fn execute_instruction(&mut self) -> u8 {
+        let begin = self.program_counter;
+        let code = self.read(begin);
+        self.program_counter = self.program_counter.wrapping_add(1);
+        let program_counter_state = self.program_counter;
+
+        let opcode = &OPCODES_TABLE[code as usize];
+        if matches!(
+            opcode.mode,
+            AddressingMode::Implied | AddressingMode::Accumulator
+        ) {
+            self.dummy_read(self.program_counter);
+        }
+
+        match opcode.instruction {
+            Instruction::ADC => {
+                self.adc(&opcode.mode);
+            }
+            Instruction::SBC => {
+                self.sbc(&opcode.mode);
+            }
+            Instruction::AND => {
+                self.and(&opcode.mode);
+            }
+            Instruction::ORA => {
+                self.ora(&opcode.mode);
+            }
+            Instruction::EOR => {
+                self.eor(&opcode.mode);
+            }
+            Instruction::ASL => {
+                self.asl(&opcode.mode);
+            }
+            Instruction::LSR => {
+                self.lsr(&opcode.mode);
+            }
+            Instruction::ROL => {
+                self.rol(&opcode.mode);
+            }
+            Instruction::ROR => {
+                self.ror(&opcode.mode);
+            }
+            Instruction::BCC => {
+                self.bcc(&opcode.mode);
+            }
+            Instruction::BCS => {
+                self.bcs(&opcode.mode);
+            }
+            Instruction::BEQ => {
+                self.beq(&opcode.mode);
+            }
+            Instruction::BNE => {
+                self.bne(&opcode.mode);
+            }
+            Instruction::BMI => {
+                self.bmi(&opcode.mode);
+            }
+            Instruction::BPL => {
+                self.bpl(&opcode.mode);
+            }
+            Instruction::BVC => {
+                self.bvc(&opcode.mode);
+            }
+            Instruction::BVS => {
+                self.bvs(&opcode.mode);
+            }
+            Instruction::BIT => {
+                self.bit(&opcode.mode);
+            }
+            Instruction::JMP => {
+                self.jmp(&opcode.mode);
+            }
+            Instruction::JSR => {
+                self.jsr();
+            }
+            Instruction::RTS => {
+                self.rts();
+            }
+            Instruction::BRK => {
+                self.brk();
+            }
+            Instruction::RTI => {
+                self.rti();
+            }
+            Instruction::CMP => {
+                self.cmp(&opcode.mode);
+            }
+            Instruction::CPX => {
+                self.cpx(&opcode.mode);
+            }
+            Instruction::CPY => {
+                self.cpy(&opcode.mode);
+            }
+            Instruction::DEC => {
+                self.dec(&opcode.mode);
+            }
+            Instruction::DEX => {
+                self.dex();
+            }
+            Instruction::DEY => {
+                self.dey();
+            }
+            Instruction::INC => {
+                self.inc(&opcode.mode);
+            }
+            Instruction::INX => {
+                self.inx();
+            }
+            Instruction::INY => {
+                self.iny();
+            }
+            Instruction::CLC => {
+                self.clc();
+            }
+            Instruction::SEC => {
+                self.sec();
+            }
+            Instruction::CLD => {
+                self.cld();
+            }
+            Instruction::SED => {
+                self.sed();
+            }
+            Instruction::CLI => {
+                self.cli();
+            }
+            Instruction::SEI => {
+                self.sei();
+            }
+            Instruction::CLV => {
+                self.clv();
+            }
+            Instruction::LDA => {
+                self.lda(&opcode.mode);
+            }
+            Instruction::LDX => {
+                self.ldx(&opcode.mode);
+            }
+            Instruction::LDY => {
+                self.ldy(&opcode.mode);
+            }
+            Instruction::STA => {
+                self.sta(&opcode.mode);
+            }
+            Instruction::STX => {
+                self.stx(&opcode.mode);
+            }
+            Instruction::STY => {
+                self.sty(&opcode.mode);
+            }
+            Instruction::TAX => {
+                self.tax();
+            }
+            Instruction::TAY => {
+                self.tay();
+            }
+            Instruction::TSX => {
+                self.tsx();
+            }
+            Instruction::TXA => {
+                self.txa();
+            }
+            Instruction::TXS => {
+                self.txs();
+            }
+            Instruction::TYA => {
+                self.tya();
+            }
+            Instruction::PHA => {
+                self.pha();
+            }
+            Instruction::PLA => {
+                self.pla();
+            }
+            Instruction::PHP => {
+                self.php();
+            }
+            Instruction::PLP => {
+                self.plp();
+            }
+            Instruction::NOP => {
+                self.nop(&opcode.mode);
+            }
+            Instruction::LAX => {
+                self.lax(&opcode.mode);
+            }
+            Instruction::SAX => {
+                self.sax(&opcode.mode);
+            }
+            Instruction::DCP => {
+                self.dcp(&opcode.mode);
+            }
+            Instruction::ISB => {
+                self.isb(&opcode.mode);
+            }
+            Instruction::SLO => {
+                self.slo(&opcode.mode);
+            }
+            Instruction::RLA => {
+                self.rla(&opcode.mode);
+            }
+            Instruction::SRE => {
+                self.sre(&opcode.mode);
+            }
+            Instruction::RRA => {
+                self.rra(&opcode.mode);
+            }
+            Instruction::LXA => {
+                self.lxa(&opcode.mode);
+            }
+            Instruction::ANC => {
+                self.anc(&opcode.mode);
+            }
+            Instruction::ALR => {
+                self.alr(&opcode.mode);
+            }
+            Instruction::ARR => {
+                self.arr(&opcode.mode);
+            }
+            Instruction::AXS => {
+                self.axs(&opcode.mode);
+            }
+            Instruction::XAA => {
+                self.xaa(&opcode.mode);
+            }
+            Instruction::LAS => {
+                self.las(&opcode.mode);
+            }
+            Instruction::TAS => {
+                self.tas(&opcode.mode);
+            }
+            Instruction::SHY => {
+                self.shy(&opcode.mode);
+            }
+            Instruction::SHX => {
+                self.shx(&opcode.mode);
+            }
+            Instruction::AHX => {
+                self.ahx(&opcode.mode);
+            }
+            Instruction::KIL => {
+                let error = EmuError::CpuJam {
+                    opcode: code,
+                    addr: begin,
+                };
+                self.illegal_opcode(error, begin);
+                return code;
+            }
+            Instruction::Unknown => {
+                let error = EmuError::UnknownOpcode {
+                    opcode: code,
+                    addr: begin,
+                };
+                self.illegal_opcode(error, begin);
+                return code;
+            }
+        }
+
+        self.complete_cycles(opcode.cycles);
+
+        if program_counter_state == self.program_counter {
+            self.program_counter = self
+                .program_counter
+                .wrapping_add((opcode.length - 1) as u16);
+        }
+
+        code
+    }
+
+    fn illegal_opcode(&mut self, error: EmuError, addr: u16) {
+        if self.bus.report(error) == ErrorPolicy::Halt {
+            self.program_counter = addr;
+            self.complete_cycles(0);
+        } else {
+            self.complete_cycles(2);
+        }
+    }
+
+    fn interrupt(&mut self, interrupt: interrupt::Interrupt) {
+        self.stack_push_u16(self.program_counter);
+        let flag = (self.status & !(BREAK_FLAG | BREAK2_FLAG)) | interrupt.b_flag_mask;
+        self.stack_push(flag);
+        self.status |= INTERRUPT_DISABLE_FLAG;
+        self.program_counter = self.fetch_u16(interrupt.vector_addr, Fetch::Execute);
     }
 
     fn adc(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
+        let value = self.get_operand_value(mode);
+        self.add_to_register_a(value);
+    }
+
+    fn sbc(&mut self, mode: &AddressingMode) {
+        let value = self.get_operand_value(mode);
+        self.sub_from_register_a(value);
+    }
+
+    fn add_to_register_a(&mut self, value: u8) {
         let carry = self.status & 0x01;
         let sum = (self.register_a as u16)
             .wrapping_add(value as u16)
@@ -409,9 +744,7 @@ impl CPU {
         self.update_zero_and_negative_flags(self.register_a);
     }
 
-    fn sbc(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
+    fn sub_from_register_a(&mut self, value: u8) {
         let carry = self.status & 0x01;
         let diff: u16 = (self.register_a as u16)
             .wrapping_sub(value as u16)
@@ -437,36 +770,34 @@ impl CPU {
     }
 
     fn and(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
-        self.register_a = self.register_a & value;
+        let value = self.get_operand_value(mode);
+        self.register_a &= value;
         self.update_zero_and_negative_flags(self.register_a);
     }
 
     fn ora(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
-        self.register_a = self.register_a | value;
+        let value = self.get_operand_value(mode);
+        self.register_a |= value;
         self.update_zero_and_negative_flags(self.register_a);
     }
 
     fn eor(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
-        self.register_a = self.register_a ^ value;
+        let value = self.get_operand_value(mode);
+        self.register_a ^= value;
         self.update_zero_and_negative_flags(self.register_a);
     }
 
-    fn asl(&mut self, mode: &AddressingMode) {
+    fn asl(&mut self, mode: &AddressingMode) -> u8 {
         let (value, carry) = if mode == &AddressingMode::Accumulator {
             let (value, carry) = self.register_a.overflowing_mul(2);
             self.register_a = value;
             (value, carry)
         } else {
-            let addr = self.get_operand_address(mode);
-            let value = self.mem_read(addr);
+            let (addr, _) = self.get_write_address(mode);
+            let value = self.read(addr);
+            self.dummy_write(addr, value);
             let (value, carry) = value.overflowing_mul(2);
-            self.mem_write(addr, value);
+            self.write(addr, value);
             (value, carry)
         };
 
@@ -476,19 +807,21 @@ impl CPU {
             self.status &= !CARRY_FLAG;
         }
         self.update_zero_and_negative_flags(value);
+        value
     }
 
-    fn lsr(&mut self, mode: &AddressingMode) {
+    fn lsr(&mut self, mode: &AddressingMode) -> u8 {
         let (value, carry) = if mode == &AddressingMode::Accumulator {
             let carry = self.register_a & 0x01;
-            self.register_a = self.register_a / 2;
+            self.register_a /= 2;
             (self.register_a, carry)
         } else {
-            let addr = self.get_operand_address(mode);
-            let value = self.mem_read(addr);
+            let (addr, _) = self.get_write_address(mode);
+            let value = self.read(addr);
+            self.dummy_write(addr, value);
             let carry = value & 0x01;
             let value = value / 2;
-            self.mem_write(addr, value);
+            self.write(addr, value);
             (value, carry)
         };
 
@@ -498,19 +831,21 @@ impl CPU {
             self.status &= !CARRY_FLAG;
         }
         self.update_zero_and_negative_flags(value);
+        value
     }
 
-    fn rol(&mut self, mode: &AddressingMode) {
+    fn rol(&mut self, mode: &AddressingMode) -> u8 {
         let (value, carry) = if mode == &AddressingMode::Accumulator {
             let (value, carry) = self.register_a.overflowing_mul(2);
             self.register_a = value | (self.status & 0x01);
             (self.register_a, carry)
         } else {
-            let addr = self.get_operand_address(mode);
-            let value = self.mem_read(addr);
+            let (addr, _) = self.get_write_address(mode);
+            let value = self.read(addr);
+            self.dummy_write(addr, value);
             let (value, carry) = value.overflowing_mul(2);
             let value = value | (self.status & 0x01);
-            self.mem_write(addr, value);
+            self.write(addr, value);
             (value, carry)
         };
 
@@ -520,21 +855,23 @@ impl CPU {
             self.status &= !CARRY_FLAG;
         }
         self.update_zero_and_negative_flags(value);
+        value
     }
 
-    fn ror(&mut self, mode: &AddressingMode) {
+    fn ror(&mut self, mode: &AddressingMode) -> u8 {
         let (value, carry) = if mode == &AddressingMode::Accumulator {
             let carry = self.register_a & 0x01;
-            self.register_a = self.register_a / 2;
-            self.register_a = self.register_a | ((self.status & 0x01) << 7);
+            self.register_a /= 2;
+            self.register_a |= (self.status & 0x01) << 7;
             (self.register_a, carry)
         } else {
-            let addr = self.get_operand_address(mode);
-            let value = self.mem_read(addr);
+            let (addr, _) = self.get_write_address(mode);
+            let value = self.read(addr);
+            self.dummy_write(addr, value);
             let carry = value & 0x01;
             let value = value / 2;
             let value = value | ((self.status & 0x01) << 7);
-            self.mem_write(addr, value);
+            self.write(addr, value);
             (value, carry)
         };
 
@@ -544,73 +881,63 @@ impl CPU {
             self.status &= !CARRY_FLAG;
         }
         self.update_zero_and_negative_flags(value);
+        value
+    }
+
+    fn branch(&mut self, mode: &AddressingMode, condition: bool) {
+        let (addr, page_cross) = self.get_operand_address(mode);
+        if condition {
+            let next = self.program_counter.wrapping_add(1);
+            self.dummy_read(next);
+            self.extra_cycles += 1;
+            if page_cross {
+                self.dummy_read((next & 0xFF00) | (addr & 0x00FF));
+                self.extra_cycles += 1;
+            }
+            self.program_counter = addr;
+        }
     }
 
     fn bcc(&mut self, mode: &AddressingMode) {
-        if self.status & 0x01 == 0 {
-            let addr = self.get_operand_address(mode);
-            self.program_counter = addr;
-        }
+        self.branch(mode, self.status & 0x01 == 0);
     }
 
     fn bcs(&mut self, mode: &AddressingMode) {
-        if self.status & 0x01 != 0 {
-            let addr = self.get_operand_address(mode);
-            self.program_counter = addr;
-        }
+        self.branch(mode, self.status & 0x01 != 0);
     }
 
     fn beq(&mut self, mode: &AddressingMode) {
-        if self.status & 0x02 != 0 {
-            let addr = self.get_operand_address(mode);
-            self.program_counter = addr;
-        }
+        self.branch(mode, self.status & 0x02 != 0);
     }
 
     fn bne(&mut self, mode: &AddressingMode) {
-        if self.status & 0x02 == 0 {
-            let addr = self.get_operand_address(mode);
-            self.program_counter = addr;
-        }
+        self.branch(mode, self.status & 0x02 == 0);
     }
 
     fn bmi(&mut self, mode: &AddressingMode) {
-        if self.status & 0x80 != 0 {
-            let addr = self.get_operand_address(mode);
-            self.program_counter = addr;
-        }
+        self.branch(mode, self.status & 0x80 != 0);
     }
 
     fn bpl(&mut self, mode: &AddressingMode) {
-        if self.status & 0x80 == 0 {
-            let addr = self.get_operand_address(mode);
-            self.program_counter = addr;
-        }
+        self.branch(mode, self.status & 0x80 == 0);
     }
 
     fn bvc(&mut self, mode: &AddressingMode) {
-        if self.status & 0x40 == 0 {
-            let addr = self.get_operand_address(mode);
-            self.program_counter = addr;
-        }
+        self.branch(mode, self.status & 0x40 == 0);
     }
 
     fn bvs(&mut self, mode: &AddressingMode) {
-        if self.status & 0x40 != 0 {
-            let addr = self.get_operand_address(mode);
-            self.program_counter = addr;
-        }
+        self.branch(mode, self.status & 0x40 != 0);
     }
 
     fn bit(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
+        let value = self.get_operand_value(mode);
         let result = self.register_a & value;
 
         if result == 0 {
-            self.status = self.status | ZERO_FLAG;
+            self.status |= ZERO_FLAG;
         } else {
-            self.status = self.status & !ZERO_FLAG
+            self.status &= !ZERO_FLAG;
         }
 
         self.status = (self.status & !(NEGATIVE_FLAG | OVERFLOW_FLAG))
@@ -618,70 +945,69 @@ impl CPU {
     }
 
     fn jmp(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
+        let (addr, _) = self.get_operand_address(mode);
         self.program_counter = addr;
     }
 
-    fn jsr(&mut self, mode: &AddressingMode) {
-        self.stack_push_u16(self.program_counter + 2 - 1);
-        let addr = self.get_operand_address(mode);
-        self.program_counter = addr;
+    fn jsr(&mut self) {
+        let lo = self.read(self.program_counter) as u16;
+        self.dummy_stack_read();
+        self.stack_push_u16(self.program_counter.wrapping_add(1));
+        let hi = self.read(self.program_counter.wrapping_add(1)) as u16;
+        self.program_counter = (hi << 8) | lo;
     }
 
     fn rts(&mut self) {
-        self.program_counter = self.stack_pop_u16() + 1;
+        self.dummy_stack_read();
+        let addr = self.stack_pop_u16();
+        self.dummy_read(addr);
+        self.program_counter = addr.wrapping_add(1);
+    }
+
+    fn brk(&mut self) {
+        self.program_counter = self.program_counter.wrapping_add(1);
+        self.interrupt(interrupt::BRK);
     }
 
     fn rti(&mut self) {
+        self.dummy_stack_read();
         self.status = (self.stack_pop() & !BREAK_FLAG) | BREAK2_FLAG;
         self.program_counter = self.stack_pop_u16();
     }
 
     fn cmp(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
-
-        if self.register_a >= value {
-            self.status = self.status | CARRY_FLAG;
-        } else {
-            self.status = self.status & !CARRY_FLAG;
-        }
-
-        self.update_zero_and_negative_flags(self.register_a.wrapping_sub(value));
+        let value = self.get_operand_value(mode);
+        self.compare(self.register_a, value);
     }
 
     fn cpx(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
-
-        if self.register_x >= value {
-            self.status = self.status | CARRY_FLAG;
-        } else {
-            self.status = self.status & !CARRY_FLAG
-        }
-
-        self.update_zero_and_negative_flags(self.register_x.wrapping_sub(value));
+        let value = self.get_operand_value(mode);
+        self.compare(self.register_x, value);
     }
 
     fn cpy(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
+        let value = self.get_operand_value(mode);
+        self.compare(self.register_y, value);
+    }
 
-        if self.register_y >= value {
-            self.status = self.status | CARRY_FLAG;
+    fn compare(&mut self, register: u8, value: u8) {
+        if register >= value {
+            self.status |= CARRY_FLAG;
         } else {
-            self.status = self.status & !CARRY_FLAG
+            self.status &= !CARRY_FLAG;
         }
 
-        self.update_zero_and_negative_flags(self.register_y.wrapping_sub(value));
+        self.update_zero_and_negative_flags(register.wrapping_sub(value));
     }
 
-    fn dec(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
+    fn dec(&mut self, mode: &AddressingMode) -> u8 {
+        let (addr, _) = self.get_write_address(mode);
+        let value = self.read(addr);
+        self.dummy_write(addr, value);
         let value = value.wrapping_sub(1);
-        self.mem_write(addr, value);
+        self.write(addr, value);
         self.update_zero_and_negative_flags(value);
+        value
     }
 
     fn dex(&mut self) {
@@ -694,12 +1020,14 @@ impl CPU {
         self.update_zero_and_negative_flags(self.register_y);
     }
 
-    fn inc(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
+    fn inc(&mut self, mode: &AddressingMode) -> u8 {
+        let (addr, _) = self.get_write_address(mode);
+        let value = self.read(addr);
+        self.dummy_write(addr, value);
         let value = value.wrapping_add(1);
-        self.mem_write(addr, value);
+        self.write(addr, value);
         self.update_zero_and_negative_flags(value);
+        value
     }
 
     fn inx(&mut self) {
@@ -713,67 +1041,64 @@ impl CPU {
     }
 
     fn clc(&mut self) {
-        self.status = self.status & !CARRY_FLAG;
+        self.status &= !CARRY_FLAG;
     }
 
     fn sec(&mut self) {
-        self.status = self.status | CARRY_FLAG;
+        self.status |= CARRY_FLAG;
     }
 
     fn cld(&mut self) {
-        self.status = self.status & !DECIMAL_FLAG;
+        self.status &= !DECIMAL_FLAG;
     }
 
     fn sed(&mut self) {
-        self.status = self.status | DECIMAL_FLAG;
+        self.status |= DECIMAL_FLAG;
     }
 
     fn cli(&mut self) {
-        self.status = self.status & !INTERRUPT_DISABLE_FLAG;
+        self.status &= !INTERRUPT_DISABLE_FLAG;
     }
 
     fn sei(&mut self) {
-        self.status = self.status | INTERRUPT_DISABLE_FLAG;
+        self.status |= INTERRUPT_DISABLE_FLAG;
     }
 
     fn clv(&mut self) {
-        self.status = self.status & !OVERFLOW_FLAG;
+        self.status &= !OVERFLOW_FLAG;
     }
 
     fn lda(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
+        let value = self.get_operand_value(mode);
         self.register_a = value;
         self.update_zero_and_negative_flags(self.register_a);
     }
 
     fn ldx(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
+        let value = self.get_operand_value(mode);
         self.register_x = value;
         self.update_zero_and_negative_flags(self.register_x);
     }
 
     fn ldy(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        let value = self.mem_read(addr);
+        let value = self.get_operand_value(mode);
         self.register_y = value;
         self.update_zero_and_negative_flags(self.register_y);
     }
 
     fn sta(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        self.mem_write(addr, self.register_a);
+        let (addr, _) = self.get_write_address(mode);
+        self.write(addr, self.register_a);
     }
 
     fn stx(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        self.mem_write(addr, self.register_x);
+        let (addr, _) = self.get_write_address(mode);
+        self.write(addr, self.register_x);
     }
 
     fn sty(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
-        self.mem_write(addr, self.register_y);
+        let (addr, _) = self.get_write_address(mode);
+        self.write(addr, self.register_y);
     }
 
     fn tax(&mut self) {
@@ -810,6 +1135,7 @@ impl CPU {
     }
 
     fn pla(&mut self) {
+        self.dummy_stack_read();
         self.register_a = self.stack_pop();
         self.update_zero_and_negative_flags(self.register_a);
     }
@@ -819,11 +1145,15 @@ impl CPU {
     }
 
     fn plp(&mut self) {
+        self.dummy_stack_read();
         self.status = (self.stack_pop() & !BREAK_FLAG) | BREAK2_FLAG;
     }
 
-    fn nop(&mut self) {
-        // no operation
+    fn nop(&mut self, mode: &AddressingMode) {
+        // no operation, but the unofficial variants still read their operand
+        if mode != &AddressingMode::Implied {
+            self.get_operand_value(mode);
+        }
     }
 
     fn lax(&mut self, mode: &AddressingMode) {
@@ -832,49 +1162,152 @@ impl CPU {
     }
 
     fn sax(&mut self, mode: &AddressingMode) {
-        let addr = self.get_operand_address(mode);
+        let (addr, _) = self.get_write_address(mode);
         let value = self.register_a & self.register_x;
-        self.mem_write(addr, value);
+        self.write(addr, value);
+    }
+
+    fn lxa(&mut self, mode: &AddressingMode) {
+        let value = self.get_operand_value(mode);
+        self.register_a = (self.register_a | self.unstable_magic) & value;
+        self.tax();
+    }
+
+    fn anc(&mut self, mode: &AddressingMode) {
+        self.and(mode);
+        if self.register_a & 0x80 != 0 {
+            self.status |= CARRY_FLAG;
+        } else {
+            self.status &= !CARRY_FLAG;
+        }
+    }
+
+    fn alr(&mut self, mode: &AddressingMode) {
+        self.and(mode);
+        self.lsr(&AddressingMode::Accumulator);
+    }
+
+    fn arr(&mut self, mode: &AddressingMode) {
+        self.and(mode);
+        self.ror(&AddressingMode::Accumulator);
+
+        let bit6 = (self.register_a >> 6) & 0x01;
+        let bit5 = (self.register_a >> 5) & 0x01;
+        if bit6 != 0 {
+            self.status |= CARRY_FLAG;
+        } else {
+            self.status &= !CARRY_FLAG;
+        }
+        if bit6 ^ bit5 != 0 {
+            self.status |= OVERFLOW_FLAG;
+        } else {
+            self.status &= !OVERFLOW_FLAG;
+        }
+    }
+
+    fn axs(&mut self, mode: &AddressingMode) {
+        let value = self.get_operand_value(mode);
+        let and = self.register_a & self.register_x;
+        self.compare(and, value);
+        self.register_x = and.wrapping_sub(value);
+    }
+
+    fn xaa(&mut self, mode: &AddressingMode) {
+        let value = self.get_operand_value(mode);
+        self.register_a = (self.register_a | self.unstable_magic) & self.register_x & value;
+        self.update_zero_and_negative_flags(self.register_a);
+    }
+
+    fn las(&mut self, mode: &AddressingMode) {
+        let value = self.get_operand_value(mode) & self.stack_pointer;
+        self.register_a = value;
+        self.register_x = value;
+        self.stack_pointer = value;
+        self.update_zero_and_negative_flags(value);
+    }
+
+    fn tas(&mut self, mode: &AddressingMode) {
+        self.stack_pointer = self.register_a & self.register_x;
+        self.store_and_high(mode, self.stack_pointer);
+    }
+
+    fn shy(&mut self, mode: &AddressingMode) {
+        self.store_and_high(mode, self.register_y);
+    }
+
+    fn shx(&mut self, mode: &AddressingMode) {
+        self.store_and_high(mode, self.register_x);
+    }
+
+    fn ahx(&mut self, mode: &AddressingMode) {
+        self.store_and_high(mode, self.register_a & self.register_x);
+    }
+
+    // Stores `value & (H + 1)`, H being the high byte of the unindexed base
+    // address. When indexing crosses a page the stored value also replaces
+    // the high byte of the target address.
+    fn store_and_high(&mut self, mode: &AddressingMode, value: u8) {
+        let (addr, page_cross) = self.get_write_address(mode);
+        let index = if mode == &AddressingMode::Absolute_X {
+            self.register_x
+        } else {
+            self.register_y
+        };
+        let base_hi = (addr.wrapping_sub(index as u16) >> 8) as u8;
+        let value = value & base_hi.wrapping_add(1);
+        let addr = if page_cross {
+            ((value as u16) << 8) | (addr & 0x00FF)
+        } else {
+            addr
+        };
+        self.write(addr, value);
     }
 
     fn dcp(&mut self, mode: &AddressingMode) {
-        self.dec(mode);
-        self.cmp(mode);
+        let value = self.dec(mode);
+        self.compare(self.register_a, value);
     }
 
     fn isb(&mut self, mode: &AddressingMode) {
-        self.inc(mode);
-        self.sbc(mode);
+        let value = self.inc(mode);
+        self.sub_from_register_a(value);
     }
 
     fn slo(&mut self, mode: &AddressingMode) {
-        self.asl(mode);
-        self.ora(mode);
+        let value = self.asl(mode);
+        self.register_a |= value;
+        self.update_zero_and_negative_flags(self.register_a);
     }
 
     fn rla(&mut self, mode: &AddressingMode) {
-        self.rol(mode);
-        self.and(mode);
+        let value = self.rol(mode);
+        self.register_a &= value;
+        self.update_zero_and_negative_flags(self.register_a);
     }
 
     fn sre(&mut self, mode: &AddressingMode) {
-        self.lsr(mode);
-        self.eor(mode);
+        let value = self.lsr(mode);
+        self.register_a ^= value;
+        self.update_zero_and_negative_flags(self.register_a);
     }
 
     fn rra(&mut self, mode: &AddressingMode) {
-        self.ror(mode);
-        self.adc(mode);
+        let value = self.ror(mode);
+        self.add_to_register_a(value);
+    }
+
+    fn dummy_stack_read(&mut self) {
+        self.dummy_read(0x100 + (self.stack_pointer as u16));
     }
 
     fn stack_push(&mut self, value: u8) {
-        self.mem_write(0x100 + (self.stack_pointer as u16), value);
+        self.write(0x100 + (self.stack_pointer as u16), value);
         self.stack_pointer = self.stack_pointer.wrapping_sub(1);
     }
 
     fn stack_pop(&mut self) -> u8 {
         self.stack_pointer = self.stack_pointer.wrapping_add(1);
-        self.mem_read(0x100 + (self.stack_pointer as u16))
+        self.read(0x100 + (self.stack_pointer as u16))
     }
 
     fn stack_push_u16(&mut self, value: u16) {
@@ -892,15 +1325,197 @@ impl CPU {
 
     fn update_zero_and_negative_flags(&mut self, result: u8) {
         if result == 0 {
-            self.status = self.status | ZERO_FLAG;
+            self.status |= ZERO_FLAG;
         } else {
-            self.status = self.status & !ZERO_FLAG;
+            self.status &= !ZERO_FLAG;
         }
 
         if result & 0b1000_0000 != 0 {
-            self.status = self.status | NEGATIVE_FLAG;
+            self.status |= NEGATIVE_FLAG;
         } else {
-            self.status = self.status & !NEGATIVE_FLAG;
+            self.status &= !NEGATIVE_FLAG;
+        }
+    }
+}
+
+fn page_cross(addr1: u16, addr2: u16) -> bool {
+    addr1 & 0xFF00 != addr2 & 0xFF00
+}
+
+fn next_in_page(addr: u16) -> u16 {
+    (addr & 0xFF00) | (addr.wrapping_add(1) & 0x00FF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cart::{Mirroring, Rom},
+        region::Region,
+    };
+
+    const PROGRAM_START: u16 = 0x0700;
+
+    fn cpu_with_program(program: &[u8], prg_rom: Vec<u8>) -> CPU {
+        cpu_with_program_at(PROGRAM_START, program, prg_rom)
+    }
+
+    // Runs `program` from RAM at `start`, with `prg_rom` mapped at $8000.
+    fn cpu_with_program_at(start: u16, program: &[u8], prg_rom: Vec<u8>) -> CPU {
+        let rom = Rom {
+            prg_rom,
+            chr_rom: vec![0; 0x2000],
+            chr_ram: false,
+            mapper: 0,
+            screen_mirroring: Mirroring::HORIZONTAL,
+            region: Region::NTSC,
+        };
+        let mut cpu = CPU::new(Bus::new(rom));
+        for (i, byte) in program.iter().enumerate() {
+            cpu.mem_write(start + i as u16, *byte);
         }
+        cpu.program_counter = start;
+        cpu
+    }
+
+    // Checks the decoder used by `trace` agrees, then executes the instruction.
+    fn step_to(cpu: &mut CPU, expected_addr: u16) {
+        let code = cpu.bus.peek(cpu.program_counter);
+        let mode = OPCODES_TABLE[code as usize].mode;
+        let (addr, _) = cpu.get_absolute_address(&mode, cpu.program_counter + 1);
+        assert_eq!(addr, expected_addr);
+        cpu.step().unwrap();
+    }
+
+    const NMI_HANDLER: u16 = 0x8123;
+
+    // Spins on JMP $0700 with the vblank NMI enabled.
+    fn cpu_waiting_for_nmi() -> CPU {
+        let mut prg_rom = vec![0; 0x4000];
+        prg_rom[0x3FFA] = NMI_HANDLER as u8;
+        prg_rom[0x3FFB] = (NMI_HANDLER >> 8) as u8;
+        let mut cpu = cpu_with_program(&[0x4C, 0x00, 0x07], prg_rom);
+        cpu.mem_write(0x2000, 0x80);
+        cpu
+    }
+
+    #[test]
+    fn step_enters_interrupt_handler_as_its_own_step() {
+        let mut cpu = cpu_waiting_for_nmi();
+        loop {
+            let (step, cycles) = cpu.step().unwrap();
+            if step == Step::Interrupt {
+                assert_eq!(cycles, 7);
+                assert_eq!(cpu.program_counter, NMI_HANDLER);
+                return;
+            }
+            assert_eq!((step, cycles), (Step::Instruction(0x4C), 3));
+        }
+    }
+
+    #[test]
+    fn run_until_stops_on_interrupt_handler_entry() {
+        let mut cpu = cpu_waiting_for_nmi();
+        let mut steps = 0;
+        cpu.run_until(|cpu| {
+            steps += 1;
+            assert!(steps < 20_000, "NMI handler never entered");
+            cpu.program_counter == NMI_HANDLER
+        })
+        .unwrap();
+        // The handler's first instruction has not run: the stack only
+        // holds the return address and status pushed on entry.
+        assert_eq!(cpu.stack_pointer, 0xFD - 3);
+    }
+
+    // Runs LDA $2002 on a cycle-stepped CPU, timed for the read to land
+    // with the PPU about to run `dot` of the vblank scanline. Returns the
+    // value read, the vblank flag afterwards, and whether an NMI followed.
+    fn read_status_at_vblank_dot(dot: u16) -> (u8, bool, bool) {
+        let mut cpu = cpu_with_program(&[0xAD, 0x02, 0x20], vec![0; 0x4000]);
+        cpu.cycle_stepped = true;
+        cpu.mem_write(0x2000, 0x80);
+        // Each of the instruction's four accesses first clocks 3 dots.
+        cpu.bus.ppu.scanline = 240;
+        cpu.bus.ppu.dot = 341 + dot - 12;
+        cpu.step().unwrap();
+        assert_eq!((cpu.bus.ppu.scanline, cpu.bus.ppu.dot), (241, dot));
+
+        cpu.bus.ppu.tick(10);
+        let vblank = cpu.bus.peek(0x2002) & 0x80 != 0;
+        (
+            cpu.register_a & 0x80,
+            vblank,
+            cpu.bus.ppu.poll_nmi_interrupt(),
+        )
+    }
+
+    #[test]
+    fn status_read_racing_vblank() {
+        // Before the flag is set: it reads clear, then is set with its NMI.
+        assert_eq!(read_status_at_vblank_dot(0), (0x00, true, true));
+        // Just before: the flag and the NMI are hidden for this frame.
+        assert_eq!(read_status_at_vblank_dot(1), (0x00, false, false));
+        // As it is set, or one dot later: the flag reads set and the read
+        // cancels the NMI.
+        assert_eq!(read_status_at_vblank_dot(2), (0x80, false, false));
+        assert_eq!(read_status_at_vblank_dot(3), (0x80, false, false));
+        // Later reads see the flag and leave the NMI alone.
+        assert_eq!(read_status_at_vblank_dot(4), (0x80, false, true));
+    }
+
+    #[test]
+    fn jmp_indirect_takes_high_byte_from_same_page() {
+        // Every page of RAM, including its mirrors, and of a 32KiB PRG-ROM.
+        for page in (0x00..=0x1F).chain(0x80..=0xFF) {
+            let ptr = (page as u16) << 8 | 0xFF;
+            let mut prg_rom = vec![0; 0x8000];
+            if ptr >= 0x8000 {
+                let offset = (ptr - 0x8000) as usize;
+                prg_rom[offset] = 0x34;
+                prg_rom[offset & 0xFF00] = 0x12;
+                prg_rom[(offset + 1) % 0x8000] = 0x56;
+            }
+            // Run from a RAM page clear of the pointer and the byte after it.
+            let start = ((page as u16 + 4) & 0x07) << 8 | 0x80;
+            let mut cpu = cpu_with_program_at(start, &[0x6C, 0xFF, page], prg_rom);
+            if ptr < 0x8000 {
+                cpu.mem_write(ptr, 0x34);
+                cpu.mem_write(ptr & 0xFF00, 0x12);
+                cpu.mem_write(ptr + 1, 0x56);
+            }
+
+            step_to(&mut cpu, 0x1234);
+            assert_eq!(cpu.program_counter, 0x1234, "JMP (${:04X})", ptr);
+        }
+    }
+
+    #[test]
+    fn indirect_x_pointer_wraps_within_zero_page() {
+        // Every base and X that land on $FF, whose high byte is at $00.
+        for base in 0x00..=0xFF {
+            let mut cpu = cpu_with_program(&[0xA1, base], vec![0; 0x4000]);
+            cpu.register_x = 0xFF - base;
+            cpu.mem_write(0x00FF, 0x34);
+            cpu.mem_write(0x0000, 0x02);
+            cpu.mem_write(0x0100, 0x05);
+            cpu.mem_write(0x0234, 0x42);
+
+            step_to(&mut cpu, 0x0234);
+            assert_eq!(cpu.register_a, 0x42, "(${:02X},X)", base);
+        }
+    }
+
+    #[test]
+    fn indirect_y_pointer_at_ff_takes_high_byte_from_zero() {
+        let mut cpu = cpu_with_program(&[0xB1, 0xFF], vec![0; 0x4000]);
+        cpu.register_y = 0x10;
+        cpu.mem_write(0x00FF, 0x34);
+        cpu.mem_write(0x0000, 0x02);
+        cpu.mem_write(0x0100, 0x05);
+        cpu.mem_write(0x0244, 0x42);
+
+        step_to(&mut cpu, 0x0244);
+        assert_eq!(cpu.register_a, 0x42);
     }
 }
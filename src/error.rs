@@ -0,0 +1,79 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmuError {
+    UnknownOpcode { opcode: u8, addr: u16 },
+    CpuJam { opcode: u8, addr: u16 },
+    IllegalBusAccess { addr: u16, access: Access },
+    IllegalVramAccess { addr: u16, access: Access },
+    BadRom(String),
+    BadPalette(String),
+}
+
+impl fmt::Display for EmuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmuError::UnknownOpcode { opcode, addr } => {
+                write!(f, "OpCode {:02X} at {:04X} is not recognized", opcode, addr)
+            }
+            EmuError::CpuJam { opcode, addr } => {
+                write!(f, "CPU jammed by OpCode {:02X} at {:04X}", opcode, addr)
+            }
+            EmuError::IllegalBusAccess { addr, access } => {
+                write!(f, "Illegal {:?} access to CPU address {:04X}", access, addr)
+            }
+            EmuError::IllegalVramAccess { addr, access } => {
+                write!(f, "Illegal {:?} access to PPU address {:04X}", access, addr)
+            }
+            EmuError::BadRom(reason) => write!(f, "Bad ROM: {}", reason),
+            EmuError::BadPalette(reason) => write!(f, "Bad palette: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for EmuError {}
+
+/// What to do when an error of a given class occurs at runtime.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorPolicy {
+    /// Stop execution and return the error from `CPU::step`.
+    Halt,
+    /// Print the error to stderr and keep running.
+    Log,
+    /// Keep running silently; illegal reads see the open-bus value.
+    #[allow(dead_code)]
+    OpenBus,
+}
+
+pub struct ErrorPolicies {
+    pub unknown_opcode: ErrorPolicy,
+    pub cpu_jam: ErrorPolicy,
+    pub bus_access: ErrorPolicy,
+}
+
+impl ErrorPolicies {
+    pub fn new() -> Self {
+        ErrorPolicies {
+            unknown_opcode: ErrorPolicy::Halt,
+            cpu_jam: ErrorPolicy::Halt,
+            bus_access: ErrorPolicy::Log,
+        }
+    }
+
+    pub fn policy_for(&self, error: &EmuError) -> ErrorPolicy {
+        match error {
+            EmuError::UnknownOpcode { .. } => self.unknown_opcode,
+            EmuError::CpuJam { .. } => self.cpu_jam,
+            EmuError::IllegalBusAccess { .. } | EmuError::IllegalVramAccess { .. } => {
+                self.bus_access
+            }
+            EmuError::BadRom(_) | EmuError::BadPalette(_) => ErrorPolicy::Halt,
+        }
+    }
+}
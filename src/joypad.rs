@@ -0,0 +1,70 @@
+/// A standard controller, read one button at a time through $4016/$4017.
+pub struct Joypad {
+    strobe: bool,
+    button_index: u8,
+    button_status: u8,
+}
+
+impl Joypad {
+    #[allow(dead_code)]
+    pub const BUTTON_A: u8 = 0b0000_0001;
+    #[allow(dead_code)]
+    pub const BUTTON_B: u8 = 0b0000_0010;
+    #[allow(dead_code)]
+    pub const SELECT: u8 = 0b0000_0100;
+    #[allow(dead_code)]
+    pub const START: u8 = 0b0000_1000;
+    #[allow(dead_code)]
+    pub const UP: u8 = 0b0001_0000;
+    #[allow(dead_code)]
+    pub const DOWN: u8 = 0b0010_0000;
+    #[allow(dead_code)]
+    pub const LEFT: u8 = 0b0100_0000;
+    #[allow(dead_code)]
+    pub const RIGHT: u8 = 0b1000_0000;
+
+    pub fn new() -> Self {
+        Joypad {
+            strobe: false,
+            button_index: 0,
+            button_status: 0,
+        }
+    }
+
+    pub fn write(&mut self, data: u8) {
+        self.strobe = data & 1 == 1;
+        if self.strobe {
+            self.button_index = 0;
+        }
+    }
+
+    /// Returns the next button's state in bit 0; after all eight, reads
+    /// return 1.
+    pub fn read(&mut self) -> u8 {
+        if self.button_index > 7 {
+            return 1;
+        }
+        let response = (self.button_status >> self.button_index) & 1;
+        if !self.strobe {
+            self.button_index += 1;
+        }
+        response
+    }
+
+    /// Returns what `read` would, without moving on to the next button.
+    pub fn peek(&self) -> u8 {
+        if self.button_index > 7 {
+            return 1;
+        }
+        (self.button_status >> self.button_index) & 1
+    }
+
+    #[allow(dead_code)]
+    pub fn set_button_pressed_status(&mut self, button: u8, pressed: bool) {
+        if pressed {
+            self.button_status |= button;
+        } else {
+            self.button_status &= !button;
+        }
+    }
+}
@@ -4,11 +4,15 @@ use cpu::CPU;
 
 use crate::trace::trace;
 
+mod apu;
 mod bus;
 mod cart;
 mod cpu;
+mod error;
+mod joypad;
 mod opcodes;
 mod ppu;
+mod region;
 mod trace;
 
 fn main() {
@@ -20,6 +24,12 @@ fn main() {
     cpu.reset();
     cpu.program_counter = 0xC000;
     cpu.run(move |cpu| {
+        // nestest returns to $0001 once every test has run
+        if cpu.program_counter == 0x0001 {
+            return false;
+        }
         println!("{}", trace(cpu));
-    });
+        true
+    })
+    .unwrap();
 }
@@ -1,20 +1,110 @@
 use crate::cpu::AddressingMode;
-use once_cell::sync::Lazy;
-use std::collections::HashMap;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Instruction {
+    ADC,
+    SBC,
+    AND,
+    EOR,
+    ORA,
+    ASL,
+    LSR,
+    ROL,
+    ROR,
+    BNE,
+    BVS,
+    BVC,
+    BMI,
+    BEQ,
+    BCS,
+    BCC,
+    BPL,
+    BIT,
+    JMP,
+    JSR,
+    RTS,
+    BRK,
+    RTI,
+    CMP,
+    CPY,
+    CPX,
+    INC,
+    INX,
+    INY,
+    DEC,
+    DEX,
+    DEY,
+    CLD,
+    CLI,
+    CLV,
+    CLC,
+    SEC,
+    SEI,
+    SED,
+    LDA,
+    LDX,
+    LDY,
+    STA,
+    STX,
+    STY,
+    TAX,
+    TAY,
+    TSX,
+    TXA,
+    TXS,
+    TYA,
+    PHA,
+    PLA,
+    PHP,
+    PLP,
+    NOP,
+    LAX,
+    LXA,
+    SAX,
+    DCP,
+    ISB,
+    SLO,
+    RLA,
+    SRE,
+    RRA,
+    ANC,
+    ALR,
+    ARR,
+    AXS,
+    XAA,
+    LAS,
+    TAS,
+    SHY,
+    SHX,
+    AHX,
+    KIL,
+    Unknown,
+}
+
+#[derive(Clone, Copy)]
 pub struct OpCode {
     pub code: u8,
     pub mnemonic: &'static str,
+    pub instruction: Instruction,
     pub length: u8,
     pub cycles: u8,
     pub mode: AddressingMode,
 }
 
 impl OpCode {
-    fn new(code: u8, mnemonic: &'static str, length: u8, cycles: u8, mode: AddressingMode) -> Self {
+    const fn new(
+        code: u8,
+        mnemonic: &'static str,
+        instruction: Instruction,
+        length: u8,
+        cycles: u8,
+        mode: AddressingMode,
+    ) -> Self {
         OpCode {
             code,
             mnemonic,
+            instruction,
             length,
             cycles,
             mode,
@@ -22,267 +112,313 @@ impl OpCode {
     }
 }
 
-static OPCODES: Lazy<Vec<OpCode>> = Lazy::new(|| {
-    vec![
-        // 演算
-        OpCode::new(0x69, "ADC", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0x65, "ADC", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x75, "ADC", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0x6d, "ADC", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0x7d, "ADC", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0x79, "ADC", 3, 4, AddressingMode::Absolute_Y),
-        OpCode::new(0x61, "ADC", 2, 6, AddressingMode::Indirect_X),
-        OpCode::new(0x71, "ADC", 2, 5, AddressingMode::Indirect_Y),
-        OpCode::new(0xe9, "SBC", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0xe5, "SBC", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0xf5, "SBC", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0xed, "SBC", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0xfd, "SBC", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0xf9, "SBC", 3, 4, AddressingMode::Absolute_Y),
-        OpCode::new(0xe1, "SBC", 2, 6, AddressingMode::Indirect_X),
-        OpCode::new(0xf1, "SBC", 2, 5, AddressingMode::Indirect_Y),
-        // 論理演算
-        OpCode::new(0x29, "AND", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0x25, "AND", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x35, "AND", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0x2d, "AND", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0x3d, "AND", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0x39, "AND", 3, 4, AddressingMode::Absolute_Y),
-        OpCode::new(0x21, "AND", 2, 6, AddressingMode::Indirect_X),
-        OpCode::new(0x31, "AND", 2, 5, AddressingMode::Indirect_Y),
-        OpCode::new(0x49, "EOR", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0x45, "EOR", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x55, "EOR", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0x4d, "EOR", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0x5d, "EOR", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0x59, "EOR", 3, 4, AddressingMode::Absolute_Y),
-        OpCode::new(0x41, "EOR", 2, 6, AddressingMode::Indirect_X),
-        OpCode::new(0x51, "EOR", 2, 5, AddressingMode::Indirect_Y),
-        OpCode::new(0x09, "ORA", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0x05, "ORA", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x15, "ORA", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0x0d, "ORA", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0x1d, "ORA", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0x19, "ORA", 3, 4, AddressingMode::Absolute_Y),
-        OpCode::new(0x01, "ORA", 2, 6, AddressingMode::Indirect_X),
-        OpCode::new(0x11, "ORA", 2, 5, AddressingMode::Indirect_Y),
-        // シフト、ローテーション
-        OpCode::new(0x0a, "ASL", 1, 2, AddressingMode::Accumulator),
-        OpCode::new(0x06, "ASL", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0x16, "ASL", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0x0e, "ASL", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0x1e, "ASL", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0x4a, "LSR", 1, 2, AddressingMode::Accumulator),
-        OpCode::new(0x46, "LSR", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0x56, "LSR", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0x4e, "LSR", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0x5e, "LSR", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0x2a, "ROL", 1, 2, AddressingMode::Accumulator),
-        OpCode::new(0x26, "ROL", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0x36, "ROL", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0x2e, "ROL", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0x3e, "ROL", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0x6a, "ROR", 1, 2, AddressingMode::Accumulator),
-        OpCode::new(0x66, "ROR", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0x76, "ROR", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0x6e, "ROR", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0x7e, "ROR", 3, 7, AddressingMode::Absolute_X),
-        // 条件分岐
-        OpCode::new(0xd0, "BNE", 2, 2, AddressingMode::RELATIVE),
-        OpCode::new(0x70, "BVS", 2, 2, AddressingMode::RELATIVE),
-        OpCode::new(0x50, "BVC", 2, 2, AddressingMode::RELATIVE),
-        OpCode::new(0x30, "BMI", 2, 2, AddressingMode::RELATIVE),
-        OpCode::new(0xf0, "BEQ", 2, 2, AddressingMode::RELATIVE),
-        OpCode::new(0xb0, "BCS", 2, 2, AddressingMode::RELATIVE),
-        OpCode::new(0x90, "BCC", 2, 2, AddressingMode::RELATIVE),
-        OpCode::new(0x10, "BPL", 2, 2, AddressingMode::RELATIVE),
-        // ビット
-        OpCode::new(0x24, "BIT", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x2c, "BIT", 3, 4, AddressingMode::Absolute),
-        // ジャンプ
-        OpCode::new(0x4c, "JMP", 3, 3, AddressingMode::Absolute),
-        OpCode::new(0x6c, "JMP", 3, 5, AddressingMode::Indirect),
-        OpCode::new(0x20, "JSR", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0x60, "RTS", 1, 6, AddressingMode::Implied),
-        // 割り込み
-        OpCode::new(0x00, "BRK", 1, 7, AddressingMode::Implied),
-        OpCode::new(0x40, "RTI", 1, 6, AddressingMode::Implied),
-        // 比較
-        OpCode::new(0xc9, "CMP", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0xc5, "CMP", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0xd5, "CMP", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0xcd, "CMP", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0xdd, "CMP", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0xd9, "CMP", 3, 4, AddressingMode::Absolute_Y),
-        OpCode::new(0xc1, "CMP", 2, 6, AddressingMode::Indirect_X),
-        OpCode::new(0xd1, "CMP", 2, 5, AddressingMode::Indirect_Y),
-        OpCode::new(0xc0, "CPY", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0xc4, "CPY", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0xcc, "CPY", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0xe0, "CPX", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0xe4, "CPX", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0xec, "CPX", 3, 4, AddressingMode::Absolute),
-        // インクリメント、デクリメント
-        OpCode::new(0xe6, "INC", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0xf6, "INC", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0xee, "INC", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0xfe, "INC", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0xe8, "INX", 1, 2, AddressingMode::Implied),
-        OpCode::new(0xc8, "INY", 1, 2, AddressingMode::Implied),
-        OpCode::new(0xc6, "DEC", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0xd6, "DEC", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0xce, "DEC", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0xde, "DEC", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0xca, "DEX", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x88, "DEY", 1, 2, AddressingMode::Implied),
-        // フラグ操作
-        OpCode::new(0xD8, "CLD", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x58, "CLI", 1, 2, AddressingMode::Implied),
-        OpCode::new(0xb8, "CLV", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x18, "CLC", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x38, "SEC", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x78, "SEI", 1, 2, AddressingMode::Implied),
-        OpCode::new(0xf8, "SED", 1, 2, AddressingMode::Implied),
-        // ロード
-        OpCode::new(0xa9, "LDA", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0xa5, "LDA", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0xb5, "LDA", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0xad, "LDA", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0xbd, "LDA", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0xb9, "LDA", 3, 4, AddressingMode::Absolute_Y),
-        OpCode::new(0xa1, "LDA", 2, 6, AddressingMode::Indirect_X),
-        OpCode::new(0xb1, "LDA", 2, 5, AddressingMode::Indirect_Y),
-        OpCode::new(0xa2, "LDX", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0xa6, "LDX", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0xb6, "LDX", 2, 4, AddressingMode::ZeroPage_Y),
-        OpCode::new(0xae, "LDX", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0xbe, "LDX", 3, 4, AddressingMode::Absolute_Y),
-        OpCode::new(0xa0, "LDY", 2, 2, AddressingMode::Immediate),
-        OpCode::new(0xa4, "LDY", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0xb4, "LDY", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0xac, "LDY", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0xbc, "LDY", 3, 4, AddressingMode::Absolute_X),
-        // ストア
-        OpCode::new(0x85, "STA", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x95, "STA", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0x8d, "STA", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0x9d, "STA", 3, 5, AddressingMode::Absolute_X),
-        OpCode::new(0x99, "STA", 3, 5, AddressingMode::Absolute_Y),
-        OpCode::new(0x81, "STA", 2, 6, AddressingMode::Indirect_X),
-        OpCode::new(0x91, "STA", 2, 6, AddressingMode::Indirect_Y),
-        OpCode::new(0x86, "STX", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x96, "STX", 2, 4, AddressingMode::ZeroPage_Y),
-        OpCode::new(0x8e, "STX", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0x84, "STY", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x94, "STY", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0x8c, "STY", 3, 4, AddressingMode::Absolute),
-        // レジスタ間転送
-        OpCode::new(0xaa, "TAX", 1, 2, AddressingMode::Implied),
-        OpCode::new(0xa8, "TAY", 1, 2, AddressingMode::Implied),
-        OpCode::new(0xba, "TSX", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x8a, "TXA", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x9a, "TXS", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x98, "TYA", 1, 2, AddressingMode::Implied),
-        // スタック
-        OpCode::new(0x48, "PHA", 1, 3, AddressingMode::Implied),
-        OpCode::new(0x68, "PLA", 1, 4, AddressingMode::Implied),
-        OpCode::new(0x08, "PHP", 1, 3, AddressingMode::Implied),
-        OpCode::new(0x28, "PLP", 1, 4, AddressingMode::Implied),
-        // NOPs
-        OpCode::new(0xea, "NOP", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x04, "*NOP", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x44, "*NOP", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x64, "*NOP", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x14, "*NOP", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0x34, "*NOP", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0x54, "*NOP", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0x74, "*NOP", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0xd4, "*NOP", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0xf4, "*NOP", 2, 4, AddressingMode::ZeroPage_X),
-        OpCode::new(0x0c, "*NOP", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0x1c, "*NOP", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0x3c, "*NOP", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0x5c, "*NOP", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0x7c, "*NOP", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0xdc, "*NOP", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0xfc, "*NOP", 3, 4, AddressingMode::Absolute_X),
-        OpCode::new(0x1a, "*NOP", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x3a, "*NOP", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x5a, "*NOP", 1, 2, AddressingMode::Implied),
-        OpCode::new(0x7a, "*NOP", 1, 2, AddressingMode::Implied),
-        OpCode::new(0xda, "*NOP", 1, 2, AddressingMode::Implied),
-        OpCode::new(0xfa, "*NOP", 1, 2, AddressingMode::Implied),
-        // SKB
-        OpCode::new(0x80, "*NOP", 2, 2, AddressingMode::Immediate),
-        // LAX
-        OpCode::new(0xa7, "*LAX", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0xb7, "*LAX", 2, 4, AddressingMode::ZeroPage_Y),
-        OpCode::new(0xaf, "*LAX", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0xbf, "*LAX", 3, 4, AddressingMode::Absolute_Y),
-        OpCode::new(0xa3, "*LAX", 2, 6, AddressingMode::Indirect_X),
-        OpCode::new(0xb3, "*LAX", 2, 5, AddressingMode::Indirect_Y),
-        // SAX
-        OpCode::new(0x87, "*SAX", 2, 3, AddressingMode::ZeroPage),
-        OpCode::new(0x97, "*SAX", 2, 4, AddressingMode::ZeroPage_Y),
-        OpCode::new(0x8f, "*SAX", 3, 4, AddressingMode::Absolute),
-        OpCode::new(0x83, "*SAX", 2, 6, AddressingMode::Indirect_X),
-        // SBC
-        OpCode::new(0xeb, "*SBC", 2, 2, AddressingMode::Immediate),
-        // DCP
-        OpCode::new(0xc7, "*DCP", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0xd7, "*DCP", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0xcf, "*DCP", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0xdf, "*DCP", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0xdb, "*DCP", 3, 7, AddressingMode::Absolute_Y),
-        OpCode::new(0xd3, "*DCP", 2, 8, AddressingMode::Indirect_Y),
-        OpCode::new(0xc3, "*DCP", 2, 8, AddressingMode::Indirect_X),
-        // ISB
-        OpCode::new(0xe7, "*ISB", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0xf7, "*ISB", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0xef, "*ISB", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0xff, "*ISB", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0xfb, "*ISB", 3, 7, AddressingMode::Absolute_Y),
-        OpCode::new(0xe3, "*ISB", 2, 8, AddressingMode::Indirect_X),
-        OpCode::new(0xf3, "*ISB", 2, 8, AddressingMode::Indirect_Y),
-        // SLO
-        OpCode::new(0x07, "*SLO", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0x17, "*SLO", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0x0F, "*SLO", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0x1f, "*SLO", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0x1b, "*SLO", 3, 7, AddressingMode::Absolute_Y),
-        OpCode::new(0x03, "*SLO", 2, 8, AddressingMode::Indirect_X),
-        OpCode::new(0x13, "*SLO", 2, 8, AddressingMode::Indirect_Y),
-        // RLA
-        OpCode::new(0x27, "*RLA", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0x37, "*RLA", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0x2f, "*RLA", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0x3f, "*RLA", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0x3b, "*RLA", 3, 7, AddressingMode::Absolute_Y),
-        OpCode::new(0x33, "*RLA", 2, 8, AddressingMode::Indirect_Y),
-        OpCode::new(0x23, "*RLA", 2, 8, AddressingMode::Indirect_X),
-        // SRE
-        OpCode::new(0x47, "*SRE", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0x57, "*SRE", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0x4f, "*SRE", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0x5f, "*SRE", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0x5b, "*SRE", 3, 7, AddressingMode::Absolute_Y),
-        OpCode::new(0x43, "*SRE", 2, 8, AddressingMode::Indirect_X),
-        OpCode::new(0x53, "*SRE", 2, 8, AddressingMode::Indirect_Y),
-        // RRA
-        OpCode::new(0x67, "*RRA", 2, 5, AddressingMode::ZeroPage),
-        OpCode::new(0x77, "*RRA", 2, 6, AddressingMode::ZeroPage_X),
-        OpCode::new(0x6f, "*RRA", 3, 6, AddressingMode::Absolute),
-        OpCode::new(0x7f, "*RRA", 3, 7, AddressingMode::Absolute_X),
-        OpCode::new(0x7b, "*RRA", 3, 7, AddressingMode::Absolute_Y),
-        OpCode::new(0x63, "*RRA", 2, 8, AddressingMode::Indirect_X),
-        OpCode::new(0x73, "*RRA", 2, 8, AddressingMode::Indirect_Y),
-    ]
-});
+#[rustfmt::skip]
+const OPCODES: &[OpCode] = &[
+    // 演算
+    OpCode::new(0x69, "ADC", Instruction::ADC, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0x65, "ADC", Instruction::ADC, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x75, "ADC", Instruction::ADC, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0x6d, "ADC", Instruction::ADC, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0x7d, "ADC", Instruction::ADC, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0x79, "ADC", Instruction::ADC, 3, 4, AddressingMode::Absolute_Y),
+    OpCode::new(0x61, "ADC", Instruction::ADC, 2, 6, AddressingMode::Indirect_X),
+    OpCode::new(0x71, "ADC", Instruction::ADC, 2, 5, AddressingMode::Indirect_Y),
+    OpCode::new(0xe9, "SBC", Instruction::SBC, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0xe5, "SBC", Instruction::SBC, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0xf5, "SBC", Instruction::SBC, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0xed, "SBC", Instruction::SBC, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0xfd, "SBC", Instruction::SBC, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0xf9, "SBC", Instruction::SBC, 3, 4, AddressingMode::Absolute_Y),
+    OpCode::new(0xe1, "SBC", Instruction::SBC, 2, 6, AddressingMode::Indirect_X),
+    OpCode::new(0xf1, "SBC", Instruction::SBC, 2, 5, AddressingMode::Indirect_Y),
+    // 論理演算
+    OpCode::new(0x29, "AND", Instruction::AND, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0x25, "AND", Instruction::AND, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x35, "AND", Instruction::AND, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0x2d, "AND", Instruction::AND, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0x3d, "AND", Instruction::AND, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0x39, "AND", Instruction::AND, 3, 4, AddressingMode::Absolute_Y),
+    OpCode::new(0x21, "AND", Instruction::AND, 2, 6, AddressingMode::Indirect_X),
+    OpCode::new(0x31, "AND", Instruction::AND, 2, 5, AddressingMode::Indirect_Y),
+    OpCode::new(0x49, "EOR", Instruction::EOR, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0x45, "EOR", Instruction::EOR, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x55, "EOR", Instruction::EOR, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0x4d, "EOR", Instruction::EOR, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0x5d, "EOR", Instruction::EOR, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0x59, "EOR", Instruction::EOR, 3, 4, AddressingMode::Absolute_Y),
+    OpCode::new(0x41, "EOR", Instruction::EOR, 2, 6, AddressingMode::Indirect_X),
+    OpCode::new(0x51, "EOR", Instruction::EOR, 2, 5, AddressingMode::Indirect_Y),
+    OpCode::new(0x09, "ORA", Instruction::ORA, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0x05, "ORA", Instruction::ORA, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x15, "ORA", Instruction::ORA, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0x0d, "ORA", Instruction::ORA, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0x1d, "ORA", Instruction::ORA, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0x19, "ORA", Instruction::ORA, 3, 4, AddressingMode::Absolute_Y),
+    OpCode::new(0x01, "ORA", Instruction::ORA, 2, 6, AddressingMode::Indirect_X),
+    OpCode::new(0x11, "ORA", Instruction::ORA, 2, 5, AddressingMode::Indirect_Y),
+    // シフト、ローテーション
+    OpCode::new(0x0a, "ASL", Instruction::ASL, 1, 2, AddressingMode::Accumulator),
+    OpCode::new(0x06, "ASL", Instruction::ASL, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0x16, "ASL", Instruction::ASL, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0x0e, "ASL", Instruction::ASL, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0x1e, "ASL", Instruction::ASL, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0x4a, "LSR", Instruction::LSR, 1, 2, AddressingMode::Accumulator),
+    OpCode::new(0x46, "LSR", Instruction::LSR, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0x56, "LSR", Instruction::LSR, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0x4e, "LSR", Instruction::LSR, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0x5e, "LSR", Instruction::LSR, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0x2a, "ROL", Instruction::ROL, 1, 2, AddressingMode::Accumulator),
+    OpCode::new(0x26, "ROL", Instruction::ROL, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0x36, "ROL", Instruction::ROL, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0x2e, "ROL", Instruction::ROL, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0x3e, "ROL", Instruction::ROL, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0x6a, "ROR", Instruction::ROR, 1, 2, AddressingMode::Accumulator),
+    OpCode::new(0x66, "ROR", Instruction::ROR, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0x76, "ROR", Instruction::ROR, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0x6e, "ROR", Instruction::ROR, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0x7e, "ROR", Instruction::ROR, 3, 7, AddressingMode::Absolute_X),
+    // 条件分岐
+    OpCode::new(0xd0, "BNE", Instruction::BNE, 2, 2, AddressingMode::RELATIVE),
+    OpCode::new(0x70, "BVS", Instruction::BVS, 2, 2, AddressingMode::RELATIVE),
+    OpCode::new(0x50, "BVC", Instruction::BVC, 2, 2, AddressingMode::RELATIVE),
+    OpCode::new(0x30, "BMI", Instruction::BMI, 2, 2, AddressingMode::RELATIVE),
+    OpCode::new(0xf0, "BEQ", Instruction::BEQ, 2, 2, AddressingMode::RELATIVE),
+    OpCode::new(0xb0, "BCS", Instruction::BCS, 2, 2, AddressingMode::RELATIVE),
+    OpCode::new(0x90, "BCC", Instruction::BCC, 2, 2, AddressingMode::RELATIVE),
+    OpCode::new(0x10, "BPL", Instruction::BPL, 2, 2, AddressingMode::RELATIVE),
+    // ビット
+    OpCode::new(0x24, "BIT", Instruction::BIT, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x2c, "BIT", Instruction::BIT, 3, 4, AddressingMode::Absolute),
+    // ジャンプ
+    OpCode::new(0x4c, "JMP", Instruction::JMP, 3, 3, AddressingMode::Absolute),
+    OpCode::new(0x6c, "JMP", Instruction::JMP, 3, 5, AddressingMode::Indirect),
+    OpCode::new(0x20, "JSR", Instruction::JSR, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0x60, "RTS", Instruction::RTS, 1, 6, AddressingMode::Implied),
+    // 割り込み
+    OpCode::new(0x00, "BRK", Instruction::BRK, 1, 7, AddressingMode::Implied),
+    OpCode::new(0x40, "RTI", Instruction::RTI, 1, 6, AddressingMode::Implied),
+    // 比較
+    OpCode::new(0xc9, "CMP", Instruction::CMP, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0xc5, "CMP", Instruction::CMP, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0xd5, "CMP", Instruction::CMP, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0xcd, "CMP", Instruction::CMP, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0xdd, "CMP", Instruction::CMP, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0xd9, "CMP", Instruction::CMP, 3, 4, AddressingMode::Absolute_Y),
+    OpCode::new(0xc1, "CMP", Instruction::CMP, 2, 6, AddressingMode::Indirect_X),
+    OpCode::new(0xd1, "CMP", Instruction::CMP, 2, 5, AddressingMode::Indirect_Y),
+    OpCode::new(0xc0, "CPY", Instruction::CPY, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0xc4, "CPY", Instruction::CPY, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0xcc, "CPY", Instruction::CPY, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0xe0, "CPX", Instruction::CPX, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0xe4, "CPX", Instruction::CPX, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0xec, "CPX", Instruction::CPX, 3, 4, AddressingMode::Absolute),
+    // インクリメント、デクリメント
+    OpCode::new(0xe6, "INC", Instruction::INC, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0xf6, "INC", Instruction::INC, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0xee, "INC", Instruction::INC, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0xfe, "INC", Instruction::INC, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0xe8, "INX", Instruction::INX, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xc8, "INY", Instruction::INY, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xc6, "DEC", Instruction::DEC, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0xd6, "DEC", Instruction::DEC, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0xce, "DEC", Instruction::DEC, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0xde, "DEC", Instruction::DEC, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0xca, "DEX", Instruction::DEX, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x88, "DEY", Instruction::DEY, 1, 2, AddressingMode::Implied),
+    // フラグ操作
+    OpCode::new(0xD8, "CLD", Instruction::CLD, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x58, "CLI", Instruction::CLI, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xb8, "CLV", Instruction::CLV, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x18, "CLC", Instruction::CLC, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x38, "SEC", Instruction::SEC, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x78, "SEI", Instruction::SEI, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xf8, "SED", Instruction::SED, 1, 2, AddressingMode::Implied),
+    // ロード
+    OpCode::new(0xa9, "LDA", Instruction::LDA, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0xa5, "LDA", Instruction::LDA, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0xb5, "LDA", Instruction::LDA, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0xad, "LDA", Instruction::LDA, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0xbd, "LDA", Instruction::LDA, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0xb9, "LDA", Instruction::LDA, 3, 4, AddressingMode::Absolute_Y),
+    OpCode::new(0xa1, "LDA", Instruction::LDA, 2, 6, AddressingMode::Indirect_X),
+    OpCode::new(0xb1, "LDA", Instruction::LDA, 2, 5, AddressingMode::Indirect_Y),
+    OpCode::new(0xa2, "LDX", Instruction::LDX, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0xa6, "LDX", Instruction::LDX, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0xb6, "LDX", Instruction::LDX, 2, 4, AddressingMode::ZeroPage_Y),
+    OpCode::new(0xae, "LDX", Instruction::LDX, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0xbe, "LDX", Instruction::LDX, 3, 4, AddressingMode::Absolute_Y),
+    OpCode::new(0xa0, "LDY", Instruction::LDY, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0xa4, "LDY", Instruction::LDY, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0xb4, "LDY", Instruction::LDY, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0xac, "LDY", Instruction::LDY, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0xbc, "LDY", Instruction::LDY, 3, 4, AddressingMode::Absolute_X),
+    // ストア
+    OpCode::new(0x85, "STA", Instruction::STA, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x95, "STA", Instruction::STA, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0x8d, "STA", Instruction::STA, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0x9d, "STA", Instruction::STA, 3, 5, AddressingMode::Absolute_X),
+    OpCode::new(0x99, "STA", Instruction::STA, 3, 5, AddressingMode::Absolute_Y),
+    OpCode::new(0x81, "STA", Instruction::STA, 2, 6, AddressingMode::Indirect_X),
+    OpCode::new(0x91, "STA", Instruction::STA, 2, 6, AddressingMode::Indirect_Y),
+    OpCode::new(0x86, "STX", Instruction::STX, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x96, "STX", Instruction::STX, 2, 4, AddressingMode::ZeroPage_Y),
+    OpCode::new(0x8e, "STX", Instruction::STX, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0x84, "STY", Instruction::STY, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x94, "STY", Instruction::STY, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0x8c, "STY", Instruction::STY, 3, 4, AddressingMode::Absolute),
+    // レジスタ間転送
+    OpCode::new(0xaa, "TAX", Instruction::TAX, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xa8, "TAY", Instruction::TAY, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xba, "TSX", Instruction::TSX, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x8a, "TXA", Instruction::TXA, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x9a, "TXS", Instruction::TXS, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x98, "TYA", Instruction::TYA, 1, 2, AddressingMode::Implied),
+    // スタック
+    OpCode::new(0x48, "PHA", Instruction::PHA, 1, 3, AddressingMode::Implied),
+    OpCode::new(0x68, "PLA", Instruction::PLA, 1, 4, AddressingMode::Implied),
+    OpCode::new(0x08, "PHP", Instruction::PHP, 1, 3, AddressingMode::Implied),
+    OpCode::new(0x28, "PLP", Instruction::PLP, 1, 4, AddressingMode::Implied),
+    // NOPs
+    OpCode::new(0xea, "NOP", Instruction::NOP, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x04, "*NOP", Instruction::NOP, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x44, "*NOP", Instruction::NOP, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x64, "*NOP", Instruction::NOP, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x14, "*NOP", Instruction::NOP, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0x34, "*NOP", Instruction::NOP, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0x54, "*NOP", Instruction::NOP, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0x74, "*NOP", Instruction::NOP, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0xd4, "*NOP", Instruction::NOP, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0xf4, "*NOP", Instruction::NOP, 2, 4, AddressingMode::ZeroPage_X),
+    OpCode::new(0x0c, "*NOP", Instruction::NOP, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0x1c, "*NOP", Instruction::NOP, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0x3c, "*NOP", Instruction::NOP, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0x5c, "*NOP", Instruction::NOP, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0x7c, "*NOP", Instruction::NOP, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0xdc, "*NOP", Instruction::NOP, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0xfc, "*NOP", Instruction::NOP, 3, 4, AddressingMode::Absolute_X),
+    OpCode::new(0x1a, "*NOP", Instruction::NOP, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x3a, "*NOP", Instruction::NOP, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x5a, "*NOP", Instruction::NOP, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x7a, "*NOP", Instruction::NOP, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xda, "*NOP", Instruction::NOP, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xfa, "*NOP", Instruction::NOP, 1, 2, AddressingMode::Implied),
+    // SKB
+    OpCode::new(0x80, "*NOP", Instruction::NOP, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0x82, "*NOP", Instruction::NOP, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0x89, "*NOP", Instruction::NOP, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0xc2, "*NOP", Instruction::NOP, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0xe2, "*NOP", Instruction::NOP, 2, 2, AddressingMode::Immediate),
+    // LAX
+    OpCode::new(0xa7, "*LAX", Instruction::LAX, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0xb7, "*LAX", Instruction::LAX, 2, 4, AddressingMode::ZeroPage_Y),
+    OpCode::new(0xaf, "*LAX", Instruction::LAX, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0xbf, "*LAX", Instruction::LAX, 3, 4, AddressingMode::Absolute_Y),
+    OpCode::new(0xa3, "*LAX", Instruction::LAX, 2, 6, AddressingMode::Indirect_X),
+    OpCode::new(0xb3, "*LAX", Instruction::LAX, 2, 5, AddressingMode::Indirect_Y),
+    OpCode::new(0xab, "*LXA", Instruction::LXA, 2, 2, AddressingMode::Immediate),
+    // SAX
+    OpCode::new(0x87, "*SAX", Instruction::SAX, 2, 3, AddressingMode::ZeroPage),
+    OpCode::new(0x97, "*SAX", Instruction::SAX, 2, 4, AddressingMode::ZeroPage_Y),
+    OpCode::new(0x8f, "*SAX", Instruction::SAX, 3, 4, AddressingMode::Absolute),
+    OpCode::new(0x83, "*SAX", Instruction::SAX, 2, 6, AddressingMode::Indirect_X),
+    // SBC
+    OpCode::new(0xeb, "*SBC", Instruction::SBC, 2, 2, AddressingMode::Immediate),
+    // DCP
+    OpCode::new(0xc7, "*DCP", Instruction::DCP, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0xd7, "*DCP", Instruction::DCP, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0xcf, "*DCP", Instruction::DCP, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0xdf, "*DCP", Instruction::DCP, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0xdb, "*DCP", Instruction::DCP, 3, 7, AddressingMode::Absolute_Y),
+    OpCode::new(0xd3, "*DCP", Instruction::DCP, 2, 8, AddressingMode::Indirect_Y),
+    OpCode::new(0xc3, "*DCP", Instruction::DCP, 2, 8, AddressingMode::Indirect_X),
+    // ISB
+    OpCode::new(0xe7, "*ISB", Instruction::ISB, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0xf7, "*ISB", Instruction::ISB, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0xef, "*ISB", Instruction::ISB, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0xff, "*ISB", Instruction::ISB, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0xfb, "*ISB", Instruction::ISB, 3, 7, AddressingMode::Absolute_Y),
+    OpCode::new(0xe3, "*ISB", Instruction::ISB, 2, 8, AddressingMode::Indirect_X),
+    OpCode::new(0xf3, "*ISB", Instruction::ISB, 2, 8, AddressingMode::Indirect_Y),
+    // SLO
+    OpCode::new(0x07, "*SLO", Instruction::SLO, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0x17, "*SLO", Instruction::SLO, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0x0F, "*SLO", Instruction::SLO, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0x1f, "*SLO", Instruction::SLO, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0x1b, "*SLO", Instruction::SLO, 3, 7, AddressingMode::Absolute_Y),
+    OpCode::new(0x03, "*SLO", Instruction::SLO, 2, 8, AddressingMode::Indirect_X),
+    OpCode::new(0x13, "*SLO", Instruction::SLO, 2, 8, AddressingMode::Indirect_Y),
+    // RLA
+    OpCode::new(0x27, "*RLA", Instruction::RLA, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0x37, "*RLA", Instruction::RLA, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0x2f, "*RLA", Instruction::RLA, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0x3f, "*RLA", Instruction::RLA, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0x3b, "*RLA", Instruction::RLA, 3, 7, AddressingMode::Absolute_Y),
+    OpCode::new(0x33, "*RLA", Instruction::RLA, 2, 8, AddressingMode::Indirect_Y),
+    OpCode::new(0x23, "*RLA", Instruction::RLA, 2, 8, AddressingMode::Indirect_X),
+    // SRE
+    OpCode::new(0x47, "*SRE", Instruction::SRE, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0x57, "*SRE", Instruction::SRE, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0x4f, "*SRE", Instruction::SRE, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0x5f, "*SRE", Instruction::SRE, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0x5b, "*SRE", Instruction::SRE, 3, 7, AddressingMode::Absolute_Y),
+    OpCode::new(0x43, "*SRE", Instruction::SRE, 2, 8, AddressingMode::Indirect_X),
+    OpCode::new(0x53, "*SRE", Instruction::SRE, 2, 8, AddressingMode::Indirect_Y),
+    // RRA
+    OpCode::new(0x67, "*RRA", Instruction::RRA, 2, 5, AddressingMode::ZeroPage),
+    OpCode::new(0x77, "*RRA", Instruction::RRA, 2, 6, AddressingMode::ZeroPage_X),
+    OpCode::new(0x6f, "*RRA", Instruction::RRA, 3, 6, AddressingMode::Absolute),
+    OpCode::new(0x7f, "*RRA", Instruction::RRA, 3, 7, AddressingMode::Absolute_X),
+    OpCode::new(0x7b, "*RRA", Instruction::RRA, 3, 7, AddressingMode::Absolute_Y),
+    OpCode::new(0x63, "*RRA", Instruction::RRA, 2, 8, AddressingMode::Indirect_X),
+    OpCode::new(0x73, "*RRA", Instruction::RRA, 2, 8, AddressingMode::Indirect_Y),
+    // 即値演算
+    OpCode::new(0x0b, "*ANC", Instruction::ANC, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0x2b, "*ANC", Instruction::ANC, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0x4b, "*ALR", Instruction::ALR, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0x6b, "*ARR", Instruction::ARR, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0xcb, "*AXS", Instruction::AXS, 2, 2, AddressingMode::Immediate),
+    OpCode::new(0x8b, "*XAA", Instruction::XAA, 2, 2, AddressingMode::Immediate),
+    // LAS
+    OpCode::new(0xbb, "*LAS", Instruction::LAS, 3, 4, AddressingMode::Absolute_Y),
+    // 上位バイト AND ストア
+    OpCode::new(0x9b, "*TAS", Instruction::TAS, 3, 5, AddressingMode::Absolute_Y),
+    OpCode::new(0x9c, "*SHY", Instruction::SHY, 3, 5, AddressingMode::Absolute_X),
+    OpCode::new(0x9e, "*SHX", Instruction::SHX, 3, 5, AddressingMode::Absolute_Y),
+    OpCode::new(0x93, "*AHX", Instruction::AHX, 2, 6, AddressingMode::Indirect_Y),
+    OpCode::new(0x9f, "*AHX", Instruction::AHX, 3, 5, AddressingMode::Absolute_Y),
+    // KIL
+    OpCode::new(0x02, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x12, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x22, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x32, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x42, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x52, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x62, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x72, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0x92, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xb2, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xd2, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+    OpCode::new(0xf2, "*KIL", Instruction::KIL, 1, 2, AddressingMode::Implied),
+];
+
+const UNKNOWN: OpCode = OpCode::new(
+    0x00,
+    "???",
+    Instruction::Unknown,
+    1,
+    2,
+    AddressingMode::Implied,
+);
 
-pub static OPCODES_MAP: Lazy<HashMap<u8, &'static OpCode>> = Lazy::new(|| {
-    let mut map = HashMap::new();
-    for opcode in &*OPCODES {
-        map.insert(opcode.code, opcode);
+/// Decoded opcodes indexed by their byte value.
+pub static OPCODES_TABLE: [OpCode; 256] = build_table();
+
+const fn build_table() -> [OpCode; 256] {
+    let mut table = [UNKNOWN; 256];
+    let mut i = 0;
+    while i < OPCODES.len() {
+        table[OPCODES[i].code as usize] = OPCODES[i];
+        i += 1;
     }
-    map
-});
+    table
+}
@@ -0,0 +1,33 @@
+use super::palette::Palette;
+
+pub const WIDTH: usize = 256;
+pub const HEIGHT: usize = 240;
+
+/// A rendered picture. Each pixel is an index into the 64-colour system
+/// palette in bits 0-5, with the PPUMASK red, green and blue emphasis bits
+/// in bits 6-8.
+pub struct Frame {
+    pub data: Vec<u16>,
+}
+
+impl Frame {
+    pub fn new() -> Self {
+        Frame {
+            data: vec![0; WIDTH * HEIGHT],
+        }
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, index: u16) {
+        self.data[y * WIDTH + x] = index;
+    }
+
+    /// Converts the frame to packed RGB triples, row by row.
+    pub fn to_rgb(&self, palette: &Palette) -> Vec<u8> {
+        let mut rgb = Vec::with_capacity(WIDTH * HEIGHT * 3);
+        for &index in &self.data {
+            let (r, g, b) = palette.color(index);
+            rgb.extend_from_slice(&[r, g, b]);
+        }
+        rgb
+    }
+}
@@ -1,109 +1,559 @@
-use self::registers::{
-    address::AddrRegister, control::ControlRegister, mask::MaskRegister, status::StatusRegister,
+use self::{
+    frame::Frame,
+    open_bus::OpenBus,
+    palette::Palette,
+    registers::{
+        control::ControlRegister, loopy::LoopyRegister, mask::MaskRegister, status::StatusRegister,
+    },
+    sprite::Sprite,
+};
+use crate::{
+    cart::Mirroring,
+    error::{Access, EmuError},
+    region::Region,
 };
-use crate::cart::Mirroring;
 
+pub mod frame;
+pub mod open_bus;
+pub mod palette;
 pub mod registers;
+pub mod sprite;
+
+const DOTS_PER_SCANLINE: u16 = 341;
+const VISIBLE_SCANLINES: u16 = 240;
+const VISIBLE_DOTS: u16 = 256;
+const SPRITE_EVALUATION_DOT: u16 = 257;
+const SPRITES_PER_SCANLINE: usize = 8;
 
+#[allow(clippy::upper_case_acronyms)]
 pub struct PPU {
     pub chr_rom: Vec<u8>,
+    pub chr_ram: bool,
     pub palette_table: [u8; 32],
-    pub vram: [u8; 2048],
+    pub vram: Vec<u8>,
+    pub oam_addr: u8,
     pub oam_data: [u8; 256],
     pub mirroring: Mirroring,
-    pub addr: AddrRegister,
+    pub region: Region,
+    pub loopy: LoopyRegister,
     pub ctrl: ControlRegister,
     pub mask: MaskRegister,
     pub status: StatusRegister,
+    pub scanline: u16,
+    pub dot: u16,
+    pub frame_count: usize,
+    pub frame: Frame,
+    /// The master palette `frame_rgb` renders with. Replace it to switch
+    /// palettes at runtime.
+    pub palette: Palette,
+    sprites: Vec<Sprite>,
+    bg_next_tile: u8,
+    bg_next_attribute: u8,
+    bg_next_pattern_lo: u8,
+    bg_next_pattern_hi: u8,
+    bg_pattern_lo: u16,
+    bg_pattern_hi: u16,
+    bg_attribute_lo: u16,
+    bg_attribute_hi: u16,
     internal_data_buf: u8,
+    open_bus: OpenBus,
+    nmi_interrupt: bool,
+    suppress_vblank: bool,
 }
 
 impl PPU {
-    pub fn new(chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+    pub fn new(chr_rom: Vec<u8>, chr_ram: bool, mirroring: Mirroring, region: Region) -> Self {
+        // Four-screen cartridges supply another 2KiB for nametables 2 and 3.
+        let vram_size = if mirroring == Mirroring::FOUR_SCREEN {
+            4096
+        } else {
+            2048
+        };
         PPU {
             chr_rom,
+            chr_ram,
             palette_table: [0; 32],
-            vram: [0; 2048],
+            vram: vec![0; vram_size],
+            oam_addr: 0,
             oam_data: [0; 64 * 4],
             mirroring,
-            addr: AddrRegister::new(),
+            region,
+            loopy: LoopyRegister::new(),
             ctrl: ControlRegister::new(),
             mask: MaskRegister::new(),
             status: StatusRegister::new(),
+            scanline: 0,
+            dot: 0,
+            frame_count: 0,
+            frame: Frame::new(),
+            palette: Palette::for_region(region),
+            sprites: Vec::with_capacity(SPRITES_PER_SCANLINE),
+            bg_next_tile: 0,
+            bg_next_attribute: 0,
+            bg_next_pattern_lo: 0,
+            bg_next_pattern_hi: 0,
+            bg_pattern_lo: 0,
+            bg_pattern_hi: 0,
+            bg_attribute_lo: 0,
+            bg_attribute_hi: 0,
             internal_data_buf: 0,
+            open_bus: OpenBus::new(),
+            nmi_interrupt: false,
+            suppress_vblank: false,
+        }
+    }
+
+    pub fn tick(&mut self, dots: u16) -> bool {
+        let mut frame_complete = false;
+        for _ in 0..dots {
+            if self.step_dot() {
+                frame_complete = true;
+            }
+        }
+        frame_complete
+    }
+
+    fn step_dot(&mut self) -> bool {
+        let vblank_scanline = self.region.vblank_scanline();
+        let pre_render_scanline = self.pre_render_scanline();
+
+        let is_render_line =
+            self.scanline < VISIBLE_SCANLINES || self.scanline == pre_render_scanline;
+        if is_render_line && self.rendering_enabled() {
+            self.step_background();
+        }
+        if self.scanline < VISIBLE_SCANLINES && (1..=VISIBLE_DOTS).contains(&self.dot) {
+            self.render_pixel(self.dot - 1);
+        }
+        if is_render_line && self.rendering_enabled() {
+            self.step_scroll();
+        }
+
+        match (self.scanline, self.dot) {
+            (scanline, 1) if scanline == vblank_scanline => {
+                let suppressed = std::mem::take(&mut self.suppress_vblank);
+                if !suppressed {
+                    self.status.set_vblank_status(true);
+                    if self.ctrl.generate_vblank_nmi() {
+                        self.nmi_interrupt = true;
+                    }
+                }
+            }
+            (scanline, 1) if scanline == pre_render_scanline => {
+                self.status.set_vblank_status(false);
+                self.status.set_sprite_zero_hit(false);
+                self.status.set_sprite_overflow(false);
+            }
+            (scanline, SPRITE_EVALUATION_DOT) if scanline < VISIBLE_SCANLINES => {
+                self.evaluate_sprites(scanline);
+            }
+            (scanline, SPRITE_EVALUATION_DOT) if scanline == pre_render_scanline => {
+                self.sprites.clear();
+            }
+            _ => {}
+        }
+
+        self.dot += 1;
+        // With rendering enabled, odd NTSC frames skip the last dot of the
+        // pre-render scanline.
+        let skip_dot = self.region.skips_odd_frame_dot()
+            && self.scanline == pre_render_scanline
+            && self.dot == DOTS_PER_SCANLINE - 1
+            && self.frame_count % 2 == 1
+            && self.rendering_enabled();
+        if self.dot < DOTS_PER_SCANLINE && !skip_dot {
+            return false;
+        }
+
+        self.dot = 0;
+        self.scanline += 1;
+        if self.scanline < self.region.scanlines_per_frame() {
+            return false;
         }
+
+        self.scanline = 0;
+        self.frame_count += 1;
+        true
+    }
+
+    fn pre_render_scanline(&self) -> u16 {
+        self.region.scanlines_per_frame() - 1
+    }
+
+    fn rendering_enabled(&self) -> bool {
+        self.mask.show_background() || self.mask.show_sprites()
+    }
+
+    // Runs the background fetch pipeline: every 8 dots the next tile's
+    // nametable, attribute and pattern bytes are fetched and loaded into the
+    // low byte of the shift registers, which shift once per dot. The first
+    // two tiles of a scanline are prefetched at the end of the previous one.
+    fn step_background(&mut self) {
+        let dot = self.dot;
+        if !((2..=VISIBLE_DOTS + 1).contains(&dot) || (321..=337).contains(&dot)) {
+            return;
+        }
+
+        self.shift_background();
+        match (dot - 1) % 8 {
+            0 => {
+                self.load_background_shifters();
+                self.bg_next_tile =
+                    self.vram[self.mirror_vram_addr(self.loopy.tile_addr()) as usize];
+            }
+            2 => {
+                let attribute =
+                    self.vram[self.mirror_vram_addr(self.loopy.attribute_addr()) as usize];
+                let shift = (self.loopy.coarse_y() & 0b10) * 2 + (self.loopy.coarse_x() & 0b10);
+                self.bg_next_attribute = (attribute >> shift) & 0b11;
+            }
+            4 => {
+                self.bg_next_pattern_lo = self.chr_rom[self.background_pattern_addr() as usize];
+            }
+            6 => {
+                self.bg_next_pattern_hi = self.chr_rom[self.background_pattern_addr() as usize + 8];
+            }
+            7 => self.loopy.increment_x(),
+            _ => {}
+        }
+    }
+
+    fn step_scroll(&mut self) {
+        match self.dot {
+            VISIBLE_DOTS => self.loopy.increment_y(),
+            SPRITE_EVALUATION_DOT => {
+                self.load_background_shifters();
+                self.loopy.copy_horizontal();
+            }
+            280..=304 if self.scanline == self.pre_render_scanline() => self.loopy.copy_vertical(),
+            _ => {}
+        }
+    }
+
+    fn background_pattern_addr(&self) -> u16 {
+        self.ctrl.bknd_pattern_addr() + self.bg_next_tile as u16 * 16 + self.loopy.fine_y()
+    }
+
+    fn shift_background(&mut self) {
+        self.bg_pattern_lo <<= 1;
+        self.bg_pattern_hi <<= 1;
+        self.bg_attribute_lo <<= 1;
+        self.bg_attribute_hi <<= 1;
+    }
+
+    fn load_background_shifters(&mut self) {
+        self.bg_pattern_lo = (self.bg_pattern_lo & 0xFF00) | self.bg_next_pattern_lo as u16;
+        self.bg_pattern_hi = (self.bg_pattern_hi & 0xFF00) | self.bg_next_pattern_hi as u16;
+        let fill = |bit: u8| if bit != 0 { 0xFF } else { 0x00 };
+        self.bg_attribute_lo =
+            (self.bg_attribute_lo & 0xFF00) | fill(self.bg_next_attribute & 0b01);
+        self.bg_attribute_hi =
+            (self.bg_attribute_hi & 0xFF00) | fill(self.bg_next_attribute & 0b10);
+    }
+
+    fn render_pixel(&mut self, x: u16) {
+        let show_background =
+            self.mask.show_background() && (x >= 8 || self.mask.show_background_leftmost());
+        let show_sprites =
+            self.mask.show_sprites() && (x >= 8 || self.mask.show_sprites_leftmost());
+
+        let background = if show_background {
+            self.background_pixel()
+        } else {
+            0
+        };
+
+        let mut color = background;
+        if show_sprites {
+            // Among overlapping sprites the lowest OAM index wins, even when
+            // it is itself hidden behind the background.
+            if let Some(sprite) = self.sprites.iter().find(|s| s.color_at(x) != 0) {
+                // Sprite 0 hit never happens at x=255.
+                if sprite.is_sprite_zero && background != 0 && x != 255 {
+                    self.status.set_sprite_zero_hit(true);
+                }
+                if background == 0 || !sprite.is_behind_background() {
+                    color = 0x10 | sprite.palette() << 2 | sprite.color_at(x);
+                }
+            }
+        }
+        let mut index = self.palette_table[self.mirror_palette_addr(color as u16) as usize] & 0x3F;
+        if self.mask.grayscale() {
+            index &= 0x30;
+        }
+        let mut emphasis = self.mask.emphasis();
+        if self.region.swaps_red_green_emphasis() {
+            emphasis = (emphasis & 0b100) | (emphasis & 0b01) << 1 | (emphasis & 0b10) >> 1;
+        }
+        let pixel = (emphasis as u16) << 6 | index as u16;
+        self.frame
+            .set_pixel(x as usize, self.scanline as usize, pixel);
+    }
+
+    // Returns the palette RAM offset of the current background pixel;
+    // transparent pixels use the universal background colour at offset 0.
+    fn background_pixel(&self) -> u8 {
+        let bit = 0x8000 >> self.loopy.fine_x();
+        let value =
+            ((self.bg_pattern_hi & bit != 0) as u8) << 1 | (self.bg_pattern_lo & bit != 0) as u8;
+        if value == 0 {
+            return 0;
+        }
+        let palette = ((self.bg_attribute_hi & bit != 0) as u8) << 1
+            | (self.bg_attribute_lo & bit != 0) as u8;
+        palette << 2 | value
+    }
+
+    // Selects the first eight sprites in OAM that cover the next scanline.
+    // OAM holds each sprite's top row minus one, so comparing it with the
+    // current scanline gives the sprite row to draw on the next one.
+    fn evaluate_sprites(&mut self, scanline: u16) {
+        self.sprites.clear();
+        if !self.rendering_enabled() {
+            return;
+        }
+
+        let height = self.ctrl.sprite_size() as u16;
+        let in_range = |y: u8| scanline.wrapping_sub(y as u16) < height;
+
+        let mut index = 0;
+        while index < 64 && self.sprites.len() < SPRITES_PER_SCANLINE {
+            let y = self.oam_data[index * 4];
+            if in_range(y) {
+                let sprite = self.fetch_sprite(index, scanline - y as u16);
+                self.sprites.push(sprite);
+            }
+            index += 1;
+        }
+
+        // Once eight sprites are found, the hardware keeps looking for a
+        // ninth to set the overflow flag, but increments the byte offset
+        // within each entry along with the entry index, so it compares
+        // tile numbers, attributes and X positions as if they were Y.
+        let mut offset = 0;
+        while index < 64 {
+            if in_range(self.oam_data[index * 4 + offset]) {
+                self.status.set_sprite_overflow(true);
+                break;
+            }
+            index += 1;
+            offset = (offset + 1) % 4;
+        }
+    }
+
+    fn fetch_sprite(&self, index: usize, row: u16) -> Sprite {
+        let tile = self.oam_data[index * 4 + 1];
+        let attributes = self.oam_data[index * 4 + 2];
+        let x = self.oam_data[index * 4 + 3];
+
+        let height = self.ctrl.sprite_size() as u16;
+        let row = if attributes & Sprite::FLIP_VERTICALLY != 0 {
+            height - 1 - row
+        } else {
+            row
+        };
+
+        // 8x16 sprites take their pattern table from bit 0 of the tile
+        // index and use the even/odd tile pair for the top/bottom halves.
+        let tile_addr = if height == 16 {
+            let bank = if tile & 1 != 0 { 0x1000 } else { 0 };
+            bank + ((tile & 0xFE) as u16 + row / 8) * 16 + row % 8
+        } else {
+            self.ctrl.sprt_pattern_addr() + tile as u16 * 16 + row
+        };
+
+        let mut pattern_lo = self.chr_rom[tile_addr as usize];
+        let mut pattern_hi = self.chr_rom[tile_addr as usize + 8];
+        if attributes & Sprite::FLIP_HORIZONTALLY != 0 {
+            pattern_lo = pattern_lo.reverse_bits();
+            pattern_hi = pattern_hi.reverse_bits();
+        }
+
+        Sprite {
+            x,
+            attributes,
+            pattern_lo,
+            pattern_hi,
+            is_sprite_zero: index == 0,
+        }
+    }
+
+    pub fn poll_nmi_interrupt(&mut self) -> bool {
+        std::mem::take(&mut self.nmi_interrupt)
+    }
+
+    fn latch(&mut self, value: u8, mask: u8) -> u8 {
+        self.open_bus.drive(value, mask, self.frame_count)
+    }
+
+    /// Converts the current frame to packed RGB with the selected palette.
+    #[allow(dead_code)]
+    pub fn frame_rgb(&self) -> Vec<u8> {
+        self.frame.to_rgb(&self.palette)
+    }
+
+    /// Reads one of the write-only registers, which returns the I/O latch.
+    pub fn read_open_bus(&mut self) -> u8 {
+        self.open_bus.get(self.frame_count)
     }
 
     pub fn write_to_ppu_addr(&mut self, value: u8) {
-        self.addr.update(value);
+        self.latch(value, 0xFF);
+        self.loopy.write_addr(value);
+    }
+
+    pub fn write_to_scroll(&mut self, value: u8) {
+        self.latch(value, 0xFF);
+        self.loopy.write_scroll(value);
+    }
+
+    /// $2002 is read-only; writing it only refreshes the I/O latch.
+    pub fn write_to_status(&mut self, value: u8) {
+        self.latch(value, 0xFF);
     }
 
     pub fn write_to_ctrl(&mut self, value: u8) {
+        self.latch(value, 0xFF);
+        let nmi_was_enabled = self.ctrl.generate_vblank_nmi();
         self.ctrl.update(value);
+        // Enabling NMI while the vblank flag is still set fires one at once.
+        if !nmi_was_enabled && self.ctrl.generate_vblank_nmi() && self.status.is_in_vblank() {
+            self.nmi_interrupt = true;
+        }
+        self.loopy.write_ctrl(value);
     }
 
     pub fn write_to_mask(&mut self, value: u8) {
+        self.latch(value, 0xFF);
         self.mask.update(value);
     }
 
+    pub fn write_to_oam_addr(&mut self, value: u8) {
+        self.latch(value, 0xFF);
+        self.oam_addr = value;
+    }
+
+    pub fn write_to_oam_data(&mut self, value: u8) {
+        self.latch(value, 0xFF);
+        // Bits 2-4 of the sprite attribute byte are not implemented and
+        // always read back as 0.
+        let value = if self.oam_addr & 3 == 2 {
+            value & 0xE3
+        } else {
+            value
+        };
+        self.oam_data[self.oam_addr as usize] = value;
+        self.oam_addr = self.oam_addr.wrapping_add(1);
+    }
+
+    pub fn read_oam_data(&mut self) -> u8 {
+        self.latch(self.oam_data[self.oam_addr as usize], 0xFF)
+    }
+
+    /// Reads $2002, acknowledging vblank. A read racing the start of vblank
+    /// hides the flag or the NMI; the race is decided on the exact dot, so
+    /// it only plays out as on hardware when `CPU::cycle_stepped` is set.
+    /// Otherwise the read lands before the instruction's dots are clocked.
     pub fn read_status(&mut self) -> u8 {
-        let value = self.status.get();
+        // Reading just before vblank starts hides the flag and the NMI for
+        // this frame; reading as it starts, or one dot later, sees the flag
+        // but still cancels the NMI.
+        if self.scanline == self.region.vblank_scanline() {
+            match self.dot {
+                1 => self.suppress_vblank = true,
+                2 | 3 => self.nmi_interrupt = false,
+                _ => {}
+            }
+        }
+        let value = self.latch(self.status.get(), !StatusRegister::PPU_OPEN_BUS);
         self.status.reset_vblank_status();
-        self.addr.reset_latch();
+        self.loopy.reset_latch();
         value
     }
 
-    pub fn read_data(&mut self) -> u8 {
-        let addr = self.addr.get();
+    pub fn read_data(&mut self) -> Result<u8, EmuError> {
+        let addr = self.loopy.get();
         self.increment_vram_addr();
 
         match addr {
             0x0000..=0x1FFF => {
                 let result = self.internal_data_buf;
                 self.internal_data_buf = self.chr_rom[addr as usize];
-                result
+                Ok(self.latch(result, 0xFF))
             }
             0x2000..=0x3EFF => {
                 let result = self.internal_data_buf;
                 self.internal_data_buf = self.vram[self.mirror_vram_addr(addr) as usize];
-                result
+                Ok(self.latch(result, 0xFF))
             }
             0x3F00..=0x3FFF => {
-                let result = self.internal_data_buf;
-                self.internal_data_buf =
-                    self.palette_table[self.mirror_palette_addr(addr) as usize];
-                result
+                // Palette reads are not buffered: they return immediately,
+                // with the top two bits from the latch, while the buffer is
+                // filled from the nametable mirrored underneath.
+                self.internal_data_buf = self.vram[self.mirror_vram_addr(addr - 0x1000) as usize];
+                let result = self.palette_table[self.mirror_palette_addr(addr) as usize];
+                Ok(self.latch(result, 0x3F))
             }
-            _ => panic!("unexpected access to mirrored space {}", addr),
+            _ => Err(EmuError::IllegalVramAccess {
+                addr,
+                access: Access::Read,
+            }),
         }
     }
 
-    pub fn write_to_data(&mut self, value: u8) {
-        let addr = self.addr.get();
+    /// Returns what reading the register at `addr` ($2000-$2007) would,
+    /// without clearing flags, moving the VRAM address, filling the read
+    /// buffer or refreshing the I/O latch.
+    pub fn peek_register(&self, addr: u16) -> u8 {
+        let latch = self.open_bus.peek(self.frame_count);
+        match addr {
+            0x2002 => {
+                (self.status.get() & !StatusRegister::PPU_OPEN_BUS)
+                    | (latch & StatusRegister::PPU_OPEN_BUS)
+            }
+            0x2004 => self.oam_data[self.oam_addr as usize],
+            0x2007 => match self.loopy.get() {
+                addr @ 0x3F00..=0x3FFF => {
+                    self.palette_table[self.mirror_palette_addr(addr) as usize] & 0x3F
+                        | (latch & 0xC0)
+                }
+                _ => self.internal_data_buf,
+            },
+            _ => latch,
+        }
+    }
+
+    pub fn write_to_data(&mut self, value: u8) -> Result<(), EmuError> {
+        self.latch(value, 0xFF);
+        let addr = self.loopy.get();
         self.increment_vram_addr();
 
         match addr {
-            0x0000..=0x1FFF => {
-                panic!(
-                    "addr space 0x0000..0x1FFF is not expected to be used, requested = {}",
-                    addr
-                )
+            0x0000..=0x1FFF if self.chr_ram => {
+                self.chr_rom[addr as usize] = value;
             }
             0x2000..=0x3EFF => {
-                self.vram[self.mirror_vram_addr(addr) as usize] = value;
+                let index = self.mirror_vram_addr(addr) as usize;
+                self.vram[index] = value;
             }
             0x3F00..=0x3FFF => {
                 self.palette_table[self.mirror_palette_addr(addr) as usize] = value;
             }
-            _ => panic!("unexpected access to mirrored space {}", addr),
+            _ => {
+                return Err(EmuError::IllegalVramAccess {
+                    addr,
+                    access: Access::Write,
+                })
+            }
         }
+        Ok(())
     }
 
     fn increment_vram_addr(&mut self) {
-        self.addr.increment(self.ctrl.vram_addr_increment());
+        self.loopy.increment(self.ctrl.vram_addr_increment());
     }
 
-    fn mirror_vram_addr(&mut self, addr: u16) -> u16 {
+    fn mirror_vram_addr(&self, addr: u16) -> u16 {
         let mirrored_vram_addr = addr & 0b10_1111_1111_1111;
         let vram_index = mirrored_vram_addr - 0x2000;
         let nametable = vram_index / 0x400;
@@ -117,7 +567,7 @@ impl PPU {
         }
     }
 
-    fn mirror_palette_addr(&mut self, addr: u16) -> u16 {
+    fn mirror_palette_addr(&self, addr: u16) -> u16 {
         let mirrored_palette_addr = addr & 0b0000_0000_0001_1111;
         match mirrored_palette_addr {
             0x10 => 0x00,
@@ -128,3 +578,44 @@ impl PPU {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_ppu() -> PPU {
+        PPU::new(vec![0; 0x2000], false, Mirroring::HORIZONTAL, Region::NTSC)
+    }
+
+    #[test]
+    fn frame_rgb_uses_the_selected_palette() {
+        let mut ppu = test_ppu();
+        ppu.frame.set_pixel(0, 0, 0x21);
+        assert_eq!(ppu.frame_rgb()[0..3], [0x0F, 0xD7, 0xFF]);
+
+        let raw: Vec<u8> = (0..64 * 3).map(|i| i as u8).collect();
+        ppu.palette = Palette::from_pal(&raw).unwrap();
+        assert_eq!(ppu.frame_rgb()[0..3], [99, 100, 101]);
+    }
+
+    // Ticks to the end of the current frame, returning the dots it took.
+    fn frame_dots(ppu: &mut PPU) -> usize {
+        let mut dots = 1;
+        while !ppu.tick(1) {
+            dots += 1;
+        }
+        dots
+    }
+
+    #[test]
+    fn odd_frames_skip_a_dot_when_rendering() {
+        let mut ppu = test_ppu();
+        assert_eq!(frame_dots(&mut ppu), 341 * 262);
+        assert_eq!(frame_dots(&mut ppu), 341 * 262);
+
+        ppu.write_to_mask(0b0000_1000);
+        assert_eq!(frame_dots(&mut ppu), 341 * 262);
+        assert_eq!(frame_dots(&mut ppu), 341 * 262 - 1);
+        assert_eq!(frame_dots(&mut ppu), 341 * 262);
+    }
+}
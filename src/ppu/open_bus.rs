@@ -0,0 +1,56 @@
+// A bit that is not refreshed decays to 0 after roughly 600 ms.
+const DECAY_FRAMES: usize = 36;
+
+/// The PPU's I/O data latch. Every register write refreshes all of its bits;
+/// reads refresh only the bits the register actually drives, and return the
+/// latch contents for the others.
+pub struct OpenBus {
+    value: u8,
+    refreshed_at: [usize; 8],
+}
+
+impl OpenBus {
+    pub fn new() -> Self {
+        OpenBus {
+            value: 0,
+            refreshed_at: [0; 8],
+        }
+    }
+
+    /// Sets the bits selected by `mask` from `value` and returns the whole
+    /// latch.
+    pub fn drive(&mut self, value: u8, mask: u8, frame: usize) -> u8 {
+        self.decay(frame);
+        self.value = (self.value & !mask) | (value & mask);
+        for bit in 0..8 {
+            if mask & (1 << bit) != 0 {
+                self.refreshed_at[bit] = frame;
+            }
+        }
+        self.value
+    }
+
+    pub fn get(&mut self, frame: usize) -> u8 {
+        self.decay(frame);
+        self.value
+    }
+
+    /// Returns the latch as `get` would, without letting bits decay.
+    pub fn peek(&self, frame: usize) -> u8 {
+        let mut value = self.value;
+        for bit in 0..8 {
+            if frame.saturating_sub(self.refreshed_at[bit]) >= DECAY_FRAMES {
+                value &= !(1 << bit);
+            }
+        }
+        value
+    }
+
+    fn decay(&mut self, frame: usize) {
+        for bit in 0..8 {
+            if frame.saturating_sub(self.refreshed_at[bit]) >= DECAY_FRAMES {
+                self.value &= !(1 << bit);
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{error::EmuError, region::Region};
+
+#[rustfmt::skip]
+static NTSC_PALETTE: [(u8, u8, u8); 64] = [
+    (0x80, 0x80, 0x80), (0x00, 0x3D, 0xA6), (0x00, 0x12, 0xB0), (0x44, 0x00, 0x96), (0xA1, 0x00, 0x5E),
+    (0xC7, 0x00, 0x28), (0xBA, 0x06, 0x00), (0x8C, 0x17, 0x00), (0x5C, 0x2F, 0x00), (0x10, 0x45, 0x00),
+    (0x05, 0x4A, 0x00), (0x00, 0x47, 0x2E), (0x00, 0x41, 0x66), (0x00, 0x00, 0x00), (0x05, 0x05, 0x05),
+    (0x05, 0x05, 0x05), (0xC7, 0xC7, 0xC7), (0x00, 0x77, 0xFF), (0x21, 0x55, 0xFF), (0x82, 0x37, 0xFA),
+    (0xEB, 0x2F, 0xB5), (0xFF, 0x29, 0x50), (0xFF, 0x22, 0x00), (0xD6, 0x32, 0x00), (0xC4, 0x62, 0x00),
+    (0x35, 0x80, 0x00), (0x05, 0x8F, 0x00), (0x00, 0x8A, 0x55), (0x00, 0x99, 0xCC), (0x21, 0x21, 0x21),
+    (0x09, 0x09, 0x09), (0x09, 0x09, 0x09), (0xFF, 0xFF, 0xFF), (0x0F, 0xD7, 0xFF), (0x69, 0xA2, 0xFF),
+    (0xD4, 0x80, 0xFF), (0xFF, 0x45, 0xF3), (0xFF, 0x61, 0x8B), (0xFF, 0x88, 0x33), (0xFF, 0x9C, 0x12),
+    (0xFA, 0xBC, 0x20), (0x9F, 0xE3, 0x0E), (0x2B, 0xF0, 0x35), (0x0C, 0xF0, 0xA4), (0x05, 0xFB, 0xFF),
+    (0x5E, 0x5E, 0x5E), (0x0D, 0x0D, 0x0D), (0x0D, 0x0D, 0x0D), (0xFF, 0xFF, 0xFF), (0xA6, 0xFC, 0xFF),
+    (0xB3, 0xEC, 0xFF), (0xDA, 0xAB, 0xEB), (0xFF, 0xA8, 0xF9), (0xFF, 0xAB, 0xB3), (0xFF, 0xD2, 0xB0),
+    (0xFF, 0xEF, 0xA6), (0xFF, 0xF7, 0x9C), (0xD7, 0xE8, 0x95), (0xA6, 0xED, 0xAF), (0xA2, 0xF2, 0xDA),
+    (0x99, 0xFF, 0xFC), (0xDD, 0xDD, 0xDD), (0x11, 0x11, 0x11), (0x11, 0x11, 0x11),
+];
+
+// The 2C07 generates every hue 15 degrees away from where the 2C02 does.
+const PAL_HUE_SHIFT_DEGREES: f32 = -15.0;
+
+// Emphasising a channel darkens the other two.
+const EMPHASIS_ATTENUATION: f32 = 0.75;
+
+/// The master palette that maps frame pixels to RGB colours.
+pub struct Palette {
+    colors: Vec<(u8, u8, u8)>,
+}
+
+impl Palette {
+    pub fn ntsc() -> Self {
+        Palette {
+            colors: NTSC_PALETTE.to_vec(),
+        }
+    }
+
+    /// The 2C07's colours, approximated by rotating the hue of every NTSC
+    /// colour. Dendy clones also output PAL video and use it too.
+    pub fn pal() -> Self {
+        Palette {
+            colors: NTSC_PALETTE
+                .iter()
+                .map(|&color| rotate_hue(color, PAL_HUE_SHIFT_DEGREES))
+                .collect(),
+        }
+    }
+
+    /// The palette of the PPU the region's consoles were built with.
+    pub fn for_region(region: Region) -> Self {
+        match region {
+            Region::NTSC => Palette::ntsc(),
+            Region::PAL | Region::DENDY => Palette::pal(),
+        }
+    }
+
+    /// Parses a .pal file: either 64 colours, or 512 colours holding all 64
+    /// for each of the eight emphasis combinations.
+    #[allow(dead_code)]
+    pub fn from_pal(raw: &[u8]) -> Result<Palette, EmuError> {
+        if raw.len() != 64 * 3 && raw.len() != 512 * 3 {
+            return Err(EmuError::BadPalette(format!(
+                "Expected 192 or 1536 bytes, got {}",
+                raw.len()
+            )));
+        }
+
+        Ok(Palette {
+            colors: raw.chunks_exact(3).map(|c| (c[0], c[1], c[2])).collect(),
+        })
+    }
+
+    /// Looks up a frame pixel, applying its emphasis bits. 64-colour
+    /// palettes approximate emphasis by attenuating the other channels.
+    pub fn color(&self, pixel: u16) -> (u8, u8, u8) {
+        if self.colors.len() == 512 {
+            return self.colors[(pixel & 0x1FF) as usize];
+        }
+
+        let (r, g, b) = self.colors[(pixel & 0x3F) as usize];
+        let emphasis = (pixel >> 6) as u8;
+        if emphasis == 0 {
+            return (r, g, b);
+        }
+
+        let attenuate = |value: u8, other_emphasis: u8| {
+            if emphasis & other_emphasis != 0 {
+                (value as f32 * EMPHASIS_ATTENUATION) as u8
+            } else {
+                value
+            }
+        };
+        (
+            attenuate(r, 0b110),
+            attenuate(g, 0b101),
+            attenuate(b, 0b011),
+        )
+    }
+}
+
+// Rotates a colour's chroma around the luma axis in YUV space.
+fn rotate_hue((r, g, b): (u8, u8, u8), degrees: f32) -> (u8, u8, u8) {
+    let (r, g, b) = (r as f32, g as f32, b as f32);
+    let y = 0.299 * r + 0.587 * g + 0.114 * b;
+    let u = 0.492 * (b - y);
+    let v = 0.877 * (r - y);
+
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (u, v) = (u * cos - v * sin, u * sin + v * cos);
+
+    let r = y + v / 0.877;
+    let b = y + u / 0.492;
+    let g = (y - 0.299 * r - 0.114 * b) / 0.587;
+    let clamp = |value: f32| value.round().clamp(0.0, 255.0) as u8;
+    (clamp(r), clamp(g), clamp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pal_palette_shifts_hues_but_keeps_greys() {
+        let ntsc = Palette::for_region(Region::NTSC);
+        let pal = Palette::for_region(Region::PAL);
+        for grey in [0x00, 0x0D, 0x10, 0x20, 0x30, 0x2D, 0x3D] {
+            assert_eq!(pal.color(grey), ntsc.color(grey), "colour {:02X}", grey);
+        }
+        for hue in 0x01..=0x0C {
+            assert_ne!(
+                pal.color(0x10 | hue),
+                ntsc.color(0x10 | hue),
+                "colour {:02X}",
+                0x10 | hue
+            );
+        }
+        assert_eq!(Palette::for_region(Region::DENDY).colors, pal.colors);
+    }
+
+    #[test]
+    fn from_pal_reads_64_colours() {
+        let raw: Vec<u8> = (0..64 * 3).map(|i| i as u8).collect();
+        let palette = Palette::from_pal(&raw).unwrap();
+        assert_eq!(palette.color(0x00), (0, 1, 2));
+        assert_eq!(palette.color(0x3F), (189, 190, 191));
+        // Emphasis is approximated by attenuating the other channels.
+        assert_eq!(palette.color(0x3F | 0b001 << 6), (189, 142, 143));
+    }
+
+    #[test]
+    fn from_pal_reads_512_colours_with_emphasis() {
+        let raw: Vec<u8> = (0..512 * 3).map(|i| (i / 3) as u8).collect();
+        let palette = Palette::from_pal(&raw).unwrap();
+        assert_eq!(palette.color(0x05), (5, 5, 5));
+        assert_eq!(palette.color(0x05 | 0b001 << 6), (69, 69, 69));
+        assert_eq!(palette.color(0x3F | 0b111 << 6), (255, 255, 255));
+    }
+
+    #[test]
+    fn from_pal_rejects_other_sizes() {
+        for len in [0, 3, 191, 193, 1535, 1537] {
+            assert!(
+                matches!(
+                    Palette::from_pal(&vec![0; len]),
+                    Err(EmuError::BadPalette(_))
+                ),
+                "{} bytes",
+                len
+            );
+        }
+    }
+}
@@ -9,23 +9,12 @@ impl ControlRegister {
     const SPRITE_PATTERN_ADDR: u8 = 0b00001000;
     const BACKROUND_PATTERN_ADDR: u8 = 0b00010000;
     const SPRITE_SIZE: u8 = 0b00100000;
-    const MASTER_SLAVE_SELECT: u8 = 0b01000000;
     const GENERATE_NMI: u8 = 0b10000000;
 
     pub fn new() -> Self {
         ControlRegister { value: 0 }
     }
 
-    pub fn nametable_addr(&self) -> u16 {
-        match self.value & 0b11 {
-            0 => 0x2000,
-            1 => 0x2400,
-            2 => 0x2800,
-            3 => 0x2C00,
-            _ => panic!("not possible"),
-        }
-    }
-
     pub fn vram_addr_increment(&self) -> u8 {
         if (self.value & ControlRegister::VRAM_ADD_INCREMENT) != 0 {
             32
@@ -58,16 +47,8 @@ impl ControlRegister {
         }
     }
 
-    pub fn master_slave_select(&self) -> u8 {
-        if (self.value & ControlRegister::MASTER_SLAVE_SELECT) != 0 {
-            1
-        } else {
-            0
-        }
-    }
-
     pub fn generate_vblank_nmi(&self) -> bool {
-        return (self.value & ControlRegister::GENERATE_NMI) != 0;
+        (self.value & ControlRegister::GENERATE_NMI) != 0
     }
 
     pub fn update(&mut self, value: u8) {
@@ -0,0 +1,143 @@
+/// The PPU's internal scroll/address registers, as described by loopy:
+/// `v` is the current VRAM address, `t` the temporary address (the top-left
+/// corner of the screen), `x` the fine X scroll and `w` the write toggle
+/// shared by $2005 and $2006.
+///
+/// `v` and `t` are laid out as `yyy NN YYYYY XXXXX`: fine Y, nametable,
+/// coarse Y and coarse X.
+pub struct LoopyRegister {
+    v: u16,
+    t: u16,
+    x: u8,
+    w: bool,
+}
+
+impl LoopyRegister {
+    const COARSE_X: u16 = 0x001F;
+    const COARSE_Y: u16 = 0x03E0;
+    const NAMETABLE_X: u16 = 0x0400;
+    const NAMETABLE_Y: u16 = 0x0800;
+    const NAMETABLE: u16 = 0x0C00;
+    const FINE_Y: u16 = 0x7000;
+    const HORIZONTAL: u16 = LoopyRegister::COARSE_X | LoopyRegister::NAMETABLE_X;
+    const VERTICAL: u16 =
+        LoopyRegister::FINE_Y | LoopyRegister::NAMETABLE_Y | LoopyRegister::COARSE_Y;
+
+    pub fn new() -> Self {
+        LoopyRegister {
+            v: 0,
+            t: 0,
+            x: 0,
+            w: false,
+        }
+    }
+
+    /// $2000 write: the nametable select bits go to `t`.
+    pub fn write_ctrl(&mut self, data: u8) {
+        self.t = (self.t & !LoopyRegister::NAMETABLE) | ((data as u16 & 0b11) << 10);
+    }
+
+    /// $2005 write: X scroll first, then Y scroll.
+    pub fn write_scroll(&mut self, data: u8) {
+        if !self.w {
+            self.t = (self.t & !LoopyRegister::COARSE_X) | (data as u16 >> 3);
+            self.x = data & 0b111;
+        } else {
+            self.t = (self.t & !(LoopyRegister::FINE_Y | LoopyRegister::COARSE_Y))
+                | ((data as u16 & 0b111) << 12)
+                | ((data as u16 >> 3) << 5);
+        }
+        self.w = !self.w;
+    }
+
+    /// $2006 write: high byte first, then low byte, which also copies `t`
+    /// into `v`.
+    pub fn write_addr(&mut self, data: u8) {
+        if !self.w {
+            self.t = (self.t & 0x00FF) | ((data as u16 & 0b11_1111) << 8);
+        } else {
+            self.t = (self.t & 0xFF00) | data as u16;
+            self.v = self.t;
+        }
+        self.w = !self.w;
+    }
+
+    pub fn reset_latch(&mut self) {
+        self.w = false;
+    }
+
+    pub fn get(&self) -> u16 {
+        self.v & 0x3FFF
+    }
+
+    pub fn increment(&mut self, inc: u8) {
+        self.v = self.v.wrapping_add(inc as u16) & 0x7FFF;
+    }
+
+    pub fn fine_x(&self) -> u8 {
+        self.x
+    }
+
+    pub fn fine_y(&self) -> u16 {
+        (self.v & LoopyRegister::FINE_Y) >> 12
+    }
+
+    pub fn coarse_x(&self) -> u16 {
+        self.v & LoopyRegister::COARSE_X
+    }
+
+    pub fn coarse_y(&self) -> u16 {
+        (self.v & LoopyRegister::COARSE_Y) >> 5
+    }
+
+    pub fn tile_addr(&self) -> u16 {
+        0x2000 | (self.v & 0x0FFF)
+    }
+
+    pub fn attribute_addr(&self) -> u16 {
+        0x23C0
+            | (self.v & LoopyRegister::NAMETABLE)
+            | ((self.v >> 4) & 0x38)
+            | ((self.v >> 2) & 0x07)
+    }
+
+    /// Moves `v` to the next tile, wrapping into the horizontally adjacent
+    /// nametable.
+    pub fn increment_x(&mut self) {
+        if self.coarse_x() == 31 {
+            self.v &= !LoopyRegister::COARSE_X;
+            self.v ^= LoopyRegister::NAMETABLE_X;
+        } else {
+            self.v += 1;
+        }
+    }
+
+    /// Moves `v` to the next pixel row, wrapping into the vertically
+    /// adjacent nametable after row 29. Coarse Y values 30 and 31 (set
+    /// through $2005/$2006) wrap to 0 without switching nametables.
+    pub fn increment_y(&mut self) {
+        if self.fine_y() < 7 {
+            self.v += 0x1000;
+            return;
+        }
+
+        self.v &= !LoopyRegister::FINE_Y;
+        let coarse_y = match self.coarse_y() {
+            29 => {
+                self.v ^= LoopyRegister::NAMETABLE_Y;
+                0
+            }
+            31 => 0,
+            y => y + 1,
+        };
+        self.v = (self.v & !LoopyRegister::COARSE_Y) | (coarse_y << 5);
+    }
+
+    pub fn copy_horizontal(&mut self) {
+        self.v = (self.v & !LoopyRegister::HORIZONTAL) | (self.t & LoopyRegister::HORIZONTAL);
+    }
+
+    pub fn copy_vertical(&mut self) {
+        self.v = (self.v & !LoopyRegister::VERTICAL) | (self.t & LoopyRegister::VERTICAL);
+    }
+}
@@ -16,6 +16,35 @@ impl MaskRegister {
         MaskRegister { value: 0 }
     }
 
+    pub fn grayscale(&self) -> bool {
+        self.value & MaskRegister::GRAYSCALE != 0
+    }
+
+    pub fn show_background_leftmost(&self) -> bool {
+        self.value & MaskRegister::SHOW_BACKGROUND_LEFTMOST_8 != 0
+    }
+
+    pub fn show_sprites_leftmost(&self) -> bool {
+        self.value & MaskRegister::SHOW_SPRITES_LEFTMOST_8 != 0
+    }
+
+    pub fn show_background(&self) -> bool {
+        self.value & MaskRegister::SHOW_BACKGROUND != 0
+    }
+
+    pub fn show_sprites(&self) -> bool {
+        self.value & MaskRegister::SHOW_SPRITES != 0
+    }
+
+    /// The red, green and blue emphasis bits, in bits 0, 1 and 2.
+    pub fn emphasis(&self) -> u8 {
+        (self.value
+            & (MaskRegister::EMPHASIZE_RED
+                | MaskRegister::EMPHASIZE_GREEN
+                | MaskRegister::EMPHASIZE_BLUE))
+            >> 5
+    }
+
     pub fn update(&mut self, value: u8) {
         self.value = value;
     }
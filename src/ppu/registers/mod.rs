@@ -1,4 +1,4 @@
-pub mod address;
 pub mod control;
+pub mod loopy;
 pub mod mask;
 pub mod status;
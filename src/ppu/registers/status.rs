@@ -3,7 +3,7 @@ pub struct StatusRegister {
 }
 
 impl StatusRegister {
-    const PPU_OPEN_BUS: u8 = 0b0001_1111;
+    pub const PPU_OPEN_BUS: u8 = 0b0001_1111;
     const SPRITE_OVERFLOW: u8 = 0b0010_0000;
     const SPRITE_ZERO_HIT: u8 = 0b0100_0000;
     const VBLANK_HAS_STARTED: u8 = 0b1000_0000;
@@ -20,6 +20,14 @@ impl StatusRegister {
         }
     }
 
+    pub fn set_sprite_overflow(&mut self, value: bool) {
+        if value {
+            self.value |= StatusRegister::SPRITE_OVERFLOW;
+        } else {
+            self.value &= !StatusRegister::SPRITE_OVERFLOW;
+        }
+    }
+
     pub fn is_in_vblank(&self) -> bool {
         self.value & StatusRegister::VBLANK_HAS_STARTED != 0
     }
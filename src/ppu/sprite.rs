@@ -0,0 +1,35 @@
+/// A sprite selected for the next scanline, with the row of its pattern
+/// that falls on that scanline already fetched.
+pub struct Sprite {
+    pub x: u8,
+    pub attributes: u8,
+    pub pattern_lo: u8,
+    pub pattern_hi: u8,
+    pub is_sprite_zero: bool,
+}
+
+impl Sprite {
+    const PALETTE: u8 = 0b0000_0011;
+    const BEHIND_BACKGROUND: u8 = 0b0010_0000;
+    pub const FLIP_HORIZONTALLY: u8 = 0b0100_0000;
+    pub const FLIP_VERTICALLY: u8 = 0b1000_0000;
+
+    /// Returns the 2-bit colour of the sprite at screen column `x`, or 0
+    /// where the sprite is transparent or does not cover the column.
+    pub fn color_at(&self, x: u16) -> u8 {
+        let column = x.wrapping_sub(self.x as u16);
+        if column >= 8 {
+            return 0;
+        }
+        let shift = 7 - column;
+        ((self.pattern_hi >> shift) & 1) << 1 | ((self.pattern_lo >> shift) & 1)
+    }
+
+    pub fn palette(&self) -> u8 {
+        self.attributes & Sprite::PALETTE
+    }
+
+    pub fn is_behind_background(&self) -> bool {
+        self.attributes & Sprite::BEHIND_BACKGROUND != 0
+    }
+}
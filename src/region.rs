@@ -0,0 +1,55 @@
+/// The TV system a console was built for, which sets its clock rates and
+/// frame timing, and the PPU's default master palette.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Region {
+    NTSC,
+    PAL,
+    /// The PAL famiclone timing used by Dendy and most other clones.
+    DENDY,
+}
+
+impl Region {
+    /// CPU cycles per second.
+    pub fn cpu_clock_rate(&self) -> u32 {
+        match self {
+            Region::NTSC => 1_789_773,
+            Region::PAL => 1_662_607,
+            Region::DENDY => 1_773_448,
+        }
+    }
+
+    /// PPU dots per CPU cycle as a (dots, cycles) ratio: 3 dots per cycle,
+    /// or 3.2 on PAL.
+    pub fn ppu_clock_ratio(&self) -> (u16, u16) {
+        match self {
+            Region::NTSC | Region::DENDY => (3, 1),
+            Region::PAL => (16, 5),
+        }
+    }
+
+    pub fn scanlines_per_frame(&self) -> u16 {
+        match self {
+            Region::NTSC => 262,
+            Region::PAL | Region::DENDY => 312,
+        }
+    }
+
+    /// Dendy keeps NTSC's 20 vblank scanlines and pads the extra 50 lines
+    /// before vblank, where PAL adds them to vblank itself.
+    pub fn vblank_scanline(&self) -> u16 {
+        match self {
+            Region::NTSC | Region::PAL => 241,
+            Region::DENDY => 291,
+        }
+    }
+
+    pub fn skips_odd_frame_dot(&self) -> bool {
+        *self == Region::NTSC
+    }
+
+    /// The 2C07 PPU swaps the PPUMASK red and green emphasis bits.
+    pub fn swaps_red_green_emphasis(&self) -> bool {
+        *self != Region::NTSC
+    }
+}
@@ -1,16 +1,12 @@
-use std::collections::HashMap;
-
 use crate::{
-    cpu::{AddressingMode, Mem, CPU},
-    opcodes,
+    cpu::{AddressingMode, CPU},
+    opcodes::OPCODES_TABLE,
 };
 
 #[allow(dead_code)]
 pub fn trace(cpu: &mut CPU) -> String {
-    let ref opscodes: HashMap<u8, &'static opcodes::OpCode> = *opcodes::OPCODES_MAP;
-
-    let code = cpu.mem_read(cpu.program_counter);
-    let ops = opscodes.get(&code).unwrap();
+    let code = cpu.bus.peek(cpu.program_counter);
+    let ops = &OPCODES_TABLE[code as usize];
 
     let begin = cpu.program_counter;
     let mut hex_dump = vec![];
@@ -19,22 +15,18 @@ pub fn trace(cpu: &mut CPU) -> String {
     let (mem_addr, stored_value) = match ops.mode {
         AddressingMode::Implied | AddressingMode::Accumulator => (0, 0),
         _ => {
-            let addr = cpu.get_absolute_address(&ops.mode, begin + 1);
-            (addr, cpu.mem_read(addr))
+            let (addr, _) = cpu.get_absolute_address(&ops.mode, begin.wrapping_add(1));
+            (addr, cpu.bus.peek(addr))
         }
     };
 
     let tmp = match ops.length {
         1 => match ops.code {
-            0x0a | 0x4a | 0x2a | 0x6a => {
-                format!("A ")
-            }
-            _ => {
-                format!("")
-            }
+            0x0a | 0x4a | 0x2a | 0x6a => "A ".to_string(),
+            _ => String::new(),
         },
         2 => {
-            let addr = cpu.mem_read(begin + 1);
+            let addr = cpu.bus.peek(begin.wrapping_add(1));
             hex_dump.push(addr);
 
             match ops.mode {
@@ -80,12 +72,12 @@ pub fn trace(cpu: &mut CPU) -> String {
             }
         }
         3 => {
-            let lo = cpu.mem_read(begin + 1);
-            let hi = cpu.mem_read(begin + 2);
+            let lo = cpu.bus.peek(begin.wrapping_add(1));
+            let hi = cpu.bus.peek(begin.wrapping_add(2));
             hex_dump.push(lo);
             hex_dump.push(hi);
 
-            let addr = cpu.mem_read_u16(begin + 1);
+            let addr = (hi as u16) << 8 | lo as u16;
 
             match ops.mode {
                 AddressingMode::Absolute => match ops.code {
@@ -127,8 +119,16 @@ pub fn trace(cpu: &mut CPU) -> String {
         .to_string();
 
     format!(
-        "{:47} A:{:02x} X:{:02x} Y:{:02x} P:{:02x} SP:{:02x}",
-        asm_str, cpu.register_a, cpu.register_x, cpu.register_y, cpu.status, cpu.stack_pointer
+        "{:47} A:{:02x} X:{:02x} Y:{:02x} P:{:02x} SP:{:02x} PPU:{:3},{:3} CYC:{}",
+        asm_str,
+        cpu.register_a,
+        cpu.register_x,
+        cpu.register_y,
+        cpu.status,
+        cpu.stack_pointer,
+        cpu.bus.ppu.scanline,
+        cpu.bus.ppu.dot,
+        cpu.bus.cycles
     )
     .to_ascii_uppercase()
 }